@@ -29,9 +29,8 @@ export async function dispatchLocalActor(
     throw new Error(`Unknown route: ${envelope.routeKey}`);
   }
 
-  const targetId = await ctx.discover(match.targetType);
-  return await ctx.callRaw(
-    targetId,
+  return await ctx.callRawByType(
+    match.targetType,
     envelope.routeKey,
     RPC_PAYLOAD_TYPE,
     envelope.payload,
@@ -4,6 +4,8 @@ export declare class ActrNode {
   /**
    * Start the node and return ActrRef.
    *
+   * Rejects if the workload's `onStart` rejects or exceeds its start timeout.
+   *
    * # Safety
    *
    * This function is unsafe because it takes ownership of the internal node and
@@ -15,13 +17,81 @@ export declare class ActrNode {
 export declare class ActrRef {
   /** Get the actor ID. */
   actorId(): ActrId
-  /** Discover actors of the given type. */
-  discover(targetType: ActrType, count: number): Promise<Array<ActrId>>
+  /**
+   * Discover actors of the given type.
+   *
+   * With `options.waitMs`, keeps re-discovering with backoff until at least
+   * `options.minCandidates` are found, rejecting with `NO_CANDIDATES` when time runs out.
+   */
+  discover(targetType: ActrType, count: number, options?: DiscoverOptions | undefined | null): Promise<Array<ActrId>>
+  /**
+   * Discover actors of the given type together with the tags they advertise.
+   *
+   * Accepts the same options as `discover`.
+   */
+  discoverWithTags(targetType: ActrType, count: number, options?: DiscoverOptions | undefined | null): Promise<Array<DiscoveredActor>>
+  /**
+   * Replace the tags this node advertises to discovering actors.
+   *
+   * Initial tags come from the `[tags]` table of `Actr.toml`. Peers see changes once their
+   * cached copy expires.
+   */
+  setTags(tags: Record<string, string>): void
+  /** Tags this node advertises. */
+  tags(): Record<string, string>
+  /**
+   * Resolve an actor of the given type from the node's cached candidates.
+   *
+   * Shares the cache and load-balancing state used by `ctx.callRawByType`; discovery runs
+   * only when the cache for the type is empty or expired.
+   */
+  resolve(targetType: ActrType, options?: DiscoverOptions | undefined | null): Promise<ActrId>
+  /**
+   * Resolve the actor of the given type that owns `shard_key` on the node's hash ring.
+   *
+   * Matches the target picked by `ctx.callRawByKey` for the same key.
+   */
+  resolveByKey(targetType: ActrType, shardKey: string): Promise<ActrId>
+  /**
+   * Watch actors of the given type joining and leaving.
+   *
+   * The first discovery round reports every current actor as `joined`. Each round also
+   * refreshes the candidates used when calling by type.
+   */
+  watch(targetType: ActrType, callback: (err: Error | null, event: DiscoveryEvent) => void, options?: WatchOptions | undefined | null): Promise<DiscoveryWatch>
+  /** Drop the cached candidates of the given type so the next call re-discovers. */
+  invalidateTargets(targetType: ActrType): void
+  /** State of every circuit breaker of the node; empty unless `circuitBreaker` is configured. */
+  circuitStates(): Array<CircuitStatus>
+  /** Close the circuits of `target`, or every circuit when omitted. */
+  resetCircuits(target?: ActrId | undefined | null): void
+  /** Drop cached responses of routes starting with `route_prefix`, or every cached response. */
+  invalidateCache(routePrefix?: string | undefined | null): void
+  /** Hit, miss and eviction counters and current size of the response cache. */
+  cacheStats(): CacheStats
   /** Call remote actor (RPC). */
-  call(routeKey: string, payloadType: PayloadType, requestPayload: Buffer, timeoutMs: number): Promise<Buffer>
+  call(routeKey: string, payloadType: PayloadType, requestPayload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
+  /**
+   * Call an actor of the given type.
+   *
+   * Uses the same cached candidates and load balancing as `ctx.callRawByType`: if the chosen
+   * target turns out to be gone, the cache is dropped and the call is retried once on a
+   * freshly discovered target.
+   */
+  callByType(targetType: ActrType, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
+  /** Send one-way message to an actor of the given type, using the cached candidates. */
+  tellByType(targetType: ActrType, routeKey: string, payloadType: PayloadType, payload: Buffer, options?: CallOptions | undefined | null): Promise<void>
+  /**
+   * Call the actor of the given type that owns `shard_key` on the node's hash ring.
+   *
+   * Picks the same target as `ctx.callRawByKey` and `resolveByKey` for the same key.
+   */
+  callByKey(targetType: ActrType, shardKey: string, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
+  /** Send one-way message to the actor of the given type that owns `shard_key`. */
+  tellByKey(targetType: ActrType, shardKey: string, routeKey: string, payloadType: PayloadType, payload: Buffer, options?: CallOptions | undefined | null): Promise<void>
   /** Send one-way message (fire-and-forget). */
-  tell(routeKey: string, payloadType: PayloadType, messagePayload: Buffer): Promise<void>
-  /** Trigger shutdown. */
+  tell(routeKey: string, payloadType: PayloadType, messagePayload: Buffer, options?: CallOptions | undefined | null): Promise<void>
+  /** Trigger shutdown. Stops every discovery watch of the node. */
   shutdown(): void
   /** Wait for shutdown to complete. */
   waitForShutdown(): Promise<void>
@@ -30,40 +100,276 @@ export declare class ActrRef {
 }
 
 export declare class ActrSystem {
-  /** Create ActrSystem from a config file path. */
-  static fromFile(configPath: string): Promise<ActrSystem>
+  /**
+   * Create ActrSystem from a config file path.
+   *
+   * The optional `[tags]` table of the file becomes the node's advertised tags, and the
+   * optional `[retry]` table supplies retry policies the attach options leave unset.
+   */
+  static fromFile(configPath: string, options?: SystemOptions | undefined | null): Promise<ActrSystem>
   /** Attach a workload and create ActrNode. */
-  attach(callback: object): ActrNode
+  attach(callback: object, options?: WorkloadOptions | undefined | null): ActrNode
+}
+
+/**
+ * Native cancellation token.
+ *
+ * The TypeScript layer creates one per `AbortSignal` and passes its `id` as
+ * `CallOptions.cancelTokenId`; calling `cancel()` rejects every call using it with `ABORTED`.
+ */
+export declare class CancellationToken {
+  constructor()
+  /** Identifier passed as `CallOptions.cancelTokenId`. */
+  get id(): number
+  /** Cancel every call using this token. */
+  cancel(): void
+  /** Whether `cancel()` has been called. */
+  get isCancelled(): boolean
+  /**
+   * Unregister the token once no call will use it; its id is rejected afterwards. Calls
+   * already using it can still be cancelled.
+   */
+  dispose(): void
 }
 
 export declare class ContextBridge {
-  /** Call remote actor. */
-  callRaw(target: ActrId, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number): Promise<Buffer>
+  /**
+   * Call remote actor.
+   *
+   * With `options.cancelTokenId`, cancelling the token drops the pending call, rejects with
+   * `ABORTED`, and notifies the target when it is known to be an actr-ts node, so its handler
+   * can stop work. Inside a handler, the timeout is capped to the request's remaining deadline
+   * and the call is cancelled along with the request.
+   */
+  callRaw(target: ActrId, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
   /** Send one-way message. */
-  tellRaw(target: ActrId, routeKey: string, payloadType: PayloadType, payload: Buffer): Promise<void>
-  /** Discover an actor of the given type. */
-  discover(targetType: ActrType): Promise<ActrId>
+  tellRaw(target: ActrId, routeKey: string, payloadType: PayloadType, payload: Buffer, options?: CallOptions | undefined | null): Promise<void>
+  /**
+   * Call an actor of the given type.
+   *
+   * Candidates are discovered once and cached per type, and one is picked per call using
+   * `options.loadBalance`. If the chosen target turns out to be gone, the cache is dropped and
+   * the call is retried once on a freshly discovered target.
+   */
+  callRawByType(targetType: ActrType, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
+  /** Send one-way message to an actor of the given type, using the cached candidates. */
+  tellRawByType(targetType: ActrType, routeKey: string, payloadType: PayloadType, payload: Buffer, options?: CallOptions | undefined | null): Promise<void>
+  /**
+   * Call the actor of the given type that owns `shard_key`.
+   *
+   * Targets are placed on a consistent-hash ring with virtual nodes, so the same key keeps
+   * reaching the same actor and only a share of keys move when actors join or leave.
+   */
+  callRawByKey(targetType: ActrType, shardKey: string, routeKey: string, payloadType: PayloadType, payload: Buffer, timeoutMs: number, options?: CallOptions | undefined | null): Promise<Buffer>
+  /** Send one-way message to the actor of the given type that owns `shard_key`. */
+  tellRawByKey(targetType: ActrType, shardKey: string, routeKey: string, payloadType: PayloadType, payload: Buffer, options?: CallOptions | undefined | null): Promise<void>
+  /**
+   * Discover an actor of the given type.
+   *
+   * With `options`, the actor is picked from the node's cached candidates using the given
+   * load-balancing strategy; without, the runtime picks one. `options.waitMs` keeps
+   * re-discovering until `options.minCandidates` are available.
+   */
+  discover(targetType: ActrType, options?: DiscoverOptions | undefined | null): Promise<ActrId>
   /** Send DataStream chunk to target. */
   sendDataStream(target: ActrId, chunk: DataStream): Promise<void>
-  /** Register a callback for an incoming DataStream. */
-  registerStream(streamId: string, callback: (err: Error | null, signal: StreamSignal) => void): Promise<void>
-  /** Unregister a DataStream callback. */
+  /**
+   * Register a callback for an incoming DataStream.
+   *
+   * A sender's `end()` arrives as a signal with `end: true`; its `abort(reason)` as an error
+   * with code `STREAM_ABORTED`. The registration is removed once the stream ends, aborts, idles
+   * past `idleTimeoutMs`, loses its sender or outlives the workload; `onClose` then receives
+   * the reason.
+   */
+  registerStream(streamId: string, callback: (err: Error | null, signal: StreamSignal) => void, options?: StreamRegistrationOptions | undefined | null, onClose?: ((err: Error | null, event: StreamClosed) => void) | undefined | null): Promise<void>
+  /** Open a stream to `target` whose writer numbers and timestamps chunks. */
+  openStream(target: ActrId, streamId: string, options?: StreamWriterOptions | undefined | null): StreamWriter
+  /** Register a buffered receiver for an incoming DataStream, read with `next()`. */
+  receiveStream(streamId: string, options?: StreamReceiverOptions | undefined | null): Promise<StreamReceiver>
+  /** Unregister a DataStream callback; its `onClose` reports `Unregistered`. */
   unregisterStream(streamId: string): Promise<void>
-  /** Get the current RPC call ID from the runtime context. */
+  /** Absolute deadline of the request being handled, in ms since Unix epoch. */
+  get deadlineMs(): number | null
+  /** Time left before the request deadline, in ms. */
+  remainingMs(): number | null
+  /**
+   * Resolve `true` when the request being handled is cancelled by its caller or its
+   * deadline passes, or `false` once the handler finishes (or outside a request).
+   */
+  waitCancelled(): Promise<boolean>
+  /** Get the ID of the actor that sent the current request. */
+  callerId(): ActrId | null
+  /**
+   * Get the current RPC call ID from the runtime context.
+   *
+   * Deprecated: returns the caller's ActrId; use `callerId()` or `envelope.caller`.
+   */
   callId(): ActrId | null
 }
 
+/**
+ * Handle of a running discovery watch.
+ *
+ * The watch stops when `close()` is called, when the handle is garbage-collected, or when the
+ * node shuts down; no events are delivered after that.
+ */
+export declare class DiscoveryWatch {
+  /** Stop watching. */
+  close(): void
+  /** Whether the watch has stopped. */
+  get isClosed(): boolean
+  /** Resolve once the watch has stopped and its last event has been delivered. */
+  closed(): Promise<void>
+}
+
+/**
+ * Receiving end of a stream, created by `ctx.receiveStream`.
+ *
+ * Chunks are read with `next()` in arrival order, or in sequence order with a reorder window,
+ * which resolves `null` once the receiver is closed. The stream handler stays registered until the
+ * stream ends, `close()` is called, the receiver is garbage-collected or its registration closes
+ * by itself.
+ */
+export declare class StreamReceiver {
+  /**
+   * Next chunk, waiting for one to arrive; `null` once the receiver is closed or the sender
+   * ended the stream. Rejects with `STREAM_ABORTED` when the sender aborted the stream, or
+   * `STREAM_OVERFLOW` when the `Error` overflow policy was hit, after the buffered chunks.
+   */
+  next(): Promise<StreamSignal | null>
+  /** Stop receiving and unregister the stream handler. Buffered chunks are discarded. */
+  close(): Promise<void>
+  /** Why the receiver's registration closed, once it has. */
+  get closeReason(): StreamCloseReason | null
+  get streamId(): string
+  /** Chunks waiting to be read. */
+  get buffered(): number
+  /** Chunks that arrived while the buffer was full and were dropped or failed the receiver. */
+  get overflowCount(): number
+  /** Delivery, loss and reorder counters of the stream. */
+  stats(): StreamStats
+}
+
+/**
+ * Sending end of a stream, created by `ctx.openStream`.
+ *
+ * Chunks get consecutive sequence numbers and the current time as timestamp; a write that fails
+ * does not use up its number. Writes are sent in call order; once `end()` or `abort()` has been
+ * sent, further writes reject with `INVALID_STATE`.
+ */
+export declare class StreamWriter {
+  /** Send `payload` as the next chunk, with `metadata` added to the stream's metadata. */
+  write(payload: Buffer, metadata?: Record<string, string> | undefined | null): Promise<void>
+  /** Finish the stream; the receiver closes once it has read every chunk. */
+  end(): Promise<void>
+  /** Fail the stream; the receiver rejects with `STREAM_ABORTED` carrying `reason`. */
+  abort(reason?: string | undefined | null): Promise<void>
+  get streamId(): string
+  get target(): ActrId
+  /** Whether `end()` or `abort()` has been sent. */
+  get isFinished(): boolean
+}
+
 export interface ActrId {
   realm: Realm
-  serialNumber: number
+  /**
+   * Full u64 serial number. Emitted as `bigint` unless the system selects
+   * `SerialNumberMode.Number`.
+   */
+  serialNumber: bigint | number
   type: ActrType
 }
 
+/** Compare two ActrIds for equality, regardless of how `serialNumber` is represented. */
+export declare function actrIdEquals(a: ActrId, b: ActrId): boolean
+
 export interface ActrType {
   manufacturer: string
   name: string
 }
 
+/** Compare two ActrTypes for equality. */
+export declare function actrTypeEquals(a: ActrType, b: ActrType): boolean
+
+/** Counters of the response cache, as reported by `ActrRef.cacheStats`. */
+export interface CacheStats {
+  hits: number
+  misses: number
+  /** Responses dropped to stay within `maxBytes`. */
+  evictions: number
+  entries: number
+  bytes: number
+}
+
+/** Circuit breaker settings for outbound calls. */
+export interface CircuitBreakerOptions {
+  /** Consecutive failures that open a circuit (default 5). */
+  failureThreshold?: number
+  /** Time a circuit stays open before probing the target, in ms (default 30000). */
+  openMs?: number
+  /** Calls let through at once while half-open (default 1). */
+  halfOpenProbes?: number
+  /** Successful probes that close a half-open circuit (default 1). */
+  successThreshold?: number
+  /** Keep a separate circuit per route key of each target (default false). */
+  perRoute?: boolean
+  /** Error codes counted as failures (default `TIMEOUT`, `UNAVAILABLE` and `TRANSPORT`). */
+  failOn?: Array<string>
+}
+
+/** State of a circuit breaker. */
+export declare const enum CircuitState {
+  /** Calls pass through. */
+  Closed = 0,
+  /** Calls are rejected with `CIRCUIT_OPEN`. */
+  Open = 1,
+  /** A limited number of probe calls pass through. */
+  HalfOpen = 2
+}
+
+/** State of one circuit, as reported by `ActrRef.circuitStates`. */
+export interface CircuitStatus {
+  target: ActrId
+  /** Set when circuits are kept per route. */
+  routeKey?: string
+  state: CircuitState
+  /** Failures since the last success. */
+  consecutiveFailures: number
+  /** When an open circuit starts probing, in ms since the Unix epoch. */
+  retryAtMs?: number
+}
+
+/** Per-call options for `callRaw`, `tellRaw`, `ActrRef.call` and `ActrRef.tell`. */
+export interface CallOptions {
+  /** Timeout in ms; takes precedence over the positional `timeoutMs`. */
+  timeoutMs?: number
+  /** Payload type; takes precedence over the positional `payloadType`. */
+  payloadType?: PayloadType
+  /** Request metadata delivered to the remote handler's `envelope.metadata`. */
+  metadata?: Record<string, string>
+  /** Delivered as the `actr-idempotency-key` metadata entry. */
+  idempotencyKey?: string
+  /** Delivered as the `actr-priority` metadata entry. */
+  priority?: number
+  /** `CancellationToken.id` that aborts the call; set from `signal` by the TypeScript layer. */
+  cancelTokenId?: number
+  /** Selection strategy when calling by type; defaults to `WorkloadOptions.loadBalance`. */
+  loadBalance?: LoadBalanceStrategy
+  /** Key hashed by `LoadBalanceStrategy.ConsistentHash` when calling by type. */
+  hashKey?: string
+  /** Only call candidates advertising all of these tags when calling by type. */
+  tags?: Record<string, string>
+  /** Retry policy; takes precedence over the node's route and default policies. */
+  retry?: RetryPolicy
+  /**
+   * Share one request among identical calls in flight at the same time; defaults to whether
+   * the route matches `WorkloadOptions.coalesceRoutes`.
+   */
+  coalesce?: boolean
+  /** Set to `false` to bypass the response cache for this call. */
+  cache?: boolean
+}
+
 export interface DataStream {
   streamId: string
   sequence: number
@@ -72,11 +378,98 @@ export interface DataStream {
   timestampMs?: number
 }
 
+/** Options for discovering candidates of a type. */
+export interface DiscoverOptions {
+  /** Selection strategy; defaults to the node's `WorkloadOptions.loadBalance`. */
+  loadBalance?: LoadBalanceStrategy
+  /** Key hashed by `LoadBalanceStrategy.ConsistentHash`. */
+  hashKey?: string
+  /**
+   * Fewest candidates to wait for (default 1). More than 1 rejects with `INVALID_STATE` before
+   * the node has started, e.g. in `onStart`.
+   */
+  minCandidates?: number
+  /**
+   * How long to keep re-discovering until `minCandidates` are found, in ms. Rejects with
+   * `NO_CANDIDATES` once it runs out (default 0: a single attempt).
+   */
+  waitMs?: number
+  /** Only candidates advertising all of these tags. */
+  tags?: Record<string, string>
+}
+
+/** A discovered actor with the tags it advertises. */
+export interface DiscoveredActor {
+  id: ActrId
+  tags: Record<string, string>
+}
+
+/** An actor of the watched type appeared or disappeared. */
+export interface DiscoveryEvent {
+  kind: 'joined' | 'left'
+  id: ActrId
+}
+
+/**
+ * Format an ActrId as `<serialNumber>@<realmId>/<manufacturer>+<name>`.
+ *
+ * The result is unique per actor and can be used as a `Map` key.
+ */
+export declare function formatActrId(id: ActrId): string
+
+/** Format an ActrType as `<manufacturer>+<name>`. */
+export declare function formatActrType(actrType: ActrType): string
+
+/** When and how often a call to an idempotent route is hedged. */
+/**
+ * 32-bit hash of an ActrId, equal for IDs that `actrIdEquals` considers equal and the same in
+ * every process.
+ *
+ * Suited to bucketing and sharding; use `formatActrId` as the `Map` key itself.
+ */
+export declare function hashActrId(id: ActrId): number
+
+export interface HedgePolicy {
+  /** Percentile of recent response times after which a hedge is sent (default 95). */
+  percentile?: number
+  /** Delay before a hedge while too few response times are known, in ms (default 100). */
+  delayMs?: number
+  /** Response times needed before the percentile is used (default 20). */
+  minSamples?: number
+  /** Extra copies sent at most, each after another delay (default 1). */
+  maxHedges?: number
+}
+
+/** How a target is chosen among the discovered candidates of a type. */
+export declare const enum LoadBalanceStrategy {
+  /** Cycle through the candidates in order. */
+  RoundRobin = 0,
+  /** Pick a candidate uniformly at random. */
+  Random = 1,
+  /** Pick the candidate with the fewest calls in flight from this node. */
+  LeastOutstanding = 2,
+  /** Pick the candidate with the lowest observed response time; unmeasured candidates first. */
+  LowestLatency = 3,
+  /** Pick by hashing `hashKey`, so the same key keeps reaching the same candidate. */
+  ConsistentHash = 4
+}
+
 export interface MetadataEntry {
   key: string
   value: string
 }
 
+/**
+ * Parse `<serialNumber>@<realmId>/<manufacturer>+<name>` into an ActrId.
+ *
+ * The serial number is a `bigint` unless `mode` is `SerialNumberMode.Number`; pass the mode of
+ * the system the ID is used with.
+ */
+export declare function parseActrId(s: string, mode?: SerialNumberMode | undefined | null): ActrId
+
+/** Parse `<manufacturer>+<name>` into an ActrType. */
+export declare function parseActrType(s: string): ActrType
+
 export declare const enum PayloadType {
   RpcReliable = 0,
   RpcSignal = 1,
@@ -89,13 +482,215 @@ export interface Realm {
   realmId: number
 }
 
+/** How `ActrId.serialNumber` is handed to JS, chosen per system with `serialNumberMode`. */
+export declare const enum SerialNumberMode {
+  /** Always a `bigint` (lossless). */
+  BigInt = 0,
+  /**
+   * Always a `number`. Serial numbers above `Number.MAX_SAFE_INTEGER` lose precision, and an
+   * ActrId carrying such a number is rejected when passed back in.
+   */
+  Number = 1
+}
+
+/** How failed calls are retried. */
+export interface RetryPolicy {
+  /** Total attempts including the first (default 3). */
+  maxAttempts?: number
+  /** Pause before the first retry, in ms (default 100). */
+  initialBackoffMs?: number
+  /** Longest pause between attempts, in ms (default 5000). */
+  maxBackoffMs?: number
+  /** Growth of the pause per retry (default 2). */
+  multiplier?: number
+  /** Fraction of each pause that is randomized, 0 to 1 (default 0.2). */
+  jitter?: number
+  /** Error codes that are retried (default `UNAVAILABLE` and `TRANSPORT`). `ABORTED` never is. */
+  retryOn?: Array<string>
+  /** Time budget for all attempts and pauses together, in ms. Rejects with `TIMEOUT` once spent. */
+  deadlineMs?: number
+}
+
+/** Settings of the node's response cache. */
+export interface ResponseCacheOptions {
+  /** Cached routes by route-key prefix, with how long a response stays valid in ms. */
+  routes: Record<string, number>
+  /** Memory bound of the cache in bytes, requests and responses together (default 16 MiB). */
+  maxBytes?: number
+}
+
 export interface RpcEnvelopeBridge {
   routeKey: string
   payload: Buffer
   requestId: string
+  metadata: Array<MetadataEntry>
+  /** W3C trace context propagated by the caller. */
+  traceparent?: string
+  tracestate?: string
+  /** Timeout requested by the caller, in ms. */
+  timeoutMs?: number
+  /** Absolute deadline (ms since Unix epoch) derived from `timeoutMs` on receipt. */
+  deadlineMs?: number
+  /** ID of the calling actor, if known. */
+  caller?: ActrId
+  /** Type of the calling actor, if known. */
+  callerType?: ActrType
+}
+
+/** Final event of a stream registration. */
+export interface StreamClosed {
+  streamId: string
+  reason: StreamCloseReason
+  /** Sender of the last chunk, if any arrived. */
+  sender?: ActrId
+}
+
+/** Why a stream registration was closed. */
+export declare const enum StreamCloseReason {
+  /** The sender called `end()`. */
+  Ended = 0,
+  /** The sender called `abort()`. */
+  Aborted = 1,
+  /**
+   * Closed on this side: `close()`, `unregisterStream` or a new registration of the same
+   * stream ID.
+   */
+  Unregistered = 2,
+  /** No chunk arrived within `idleTimeoutMs`. */
+  IdleTimeout = 3,
+  /** The sender is no longer registered. */
+  PeerDisconnected = 4,
+  /** The workload stopped. */
+  WorkloadStopped = 5,
+  /** A chunk arrived while the receiver's buffer was full and its overflow policy is `Error`. */
+  Overflowed = 6
+}
+
+/** What a receiver does with a chunk that arrives while its buffer is full. */
+export declare const enum StreamOverflow {
+  /** Hold the chunk until the consumer frees a slot, pausing delivery from the runtime. */
+  Block = 0,
+  /** Drop the oldest buffered chunk to make room. */
+  DropOldest = 1,
+  /** Drop the incoming chunk. */
+  DropNewest = 2,
+  /** Fail the receiver with `STREAM_OVERFLOW` once the buffered chunks are read. */
+  Error = 3
+}
+
+/** Options for `ctx.receiveStream`. */
+export interface StreamReceiverOptions {
+  /** Chunks buffered before `overflow` applies (default 64). */
+  highWaterMark?: number
+  /** What to do when the buffer is full (default `Block`). */
+  overflow?: StreamOverflow
+  /**
+   * Chunks held back to restore sequence order; missing sequence numbers are skipped once
+   * more are waiting (default 0: deliver in arrival order).
+   */
+  reorderWindow?: number
+  /**
+   * With a reorder window, skip missing sequence numbers once a held chunk has waited this
+   * long, in ms. Meant for `StreamLatencyFirst` streams, where lost chunks are not resent.
+   * Requires `reorderWindow`.
+   */
+  jitterMs?: number
+  /**
+   * With a reorder window, sequence number of the sender's first chunk (default 0, as for
+   * `ctx.openStream`).
+   */
+  startSequence?: number
+  /** Close the receiver when no chunk arrives for this long, in ms (default: never). */
+  idleTimeoutMs?: number
+  /** How often the sender is checked to still be registered, in ms (default: never). */
+  peerCheckMs?: number
+}
+
+/** Options for `ctx.registerStream`. */
+export interface StreamRegistrationOptions {
+  /** Close the registration when no chunk arrives for this long, in ms (default: never). */
+  idleTimeoutMs?: number
+  /** How often the sender is checked to still be registered, in ms (default: never). */
+  peerCheckMs?: number
 }
 
 export interface StreamSignal {
   chunk: DataStream
   sender: ActrId
+  /** Set on the control chunk a sender's `end()` delivers to a `registerStream` callback. */
+  end?: boolean
+  /** Sequence numbers a reordering receiver skipped right before this chunk. */
+  missed?: number
+}
+
+/** Counters of a stream receiver, as reported by `StreamReceiver.stats`. */
+export interface StreamStats {
+  /** Data chunks received from the runtime. */
+  received: number
+  /** Chunks read with `next()`. */
+  delivered: number
+  /** Chunks dropped because their sequence number was already delivered or skipped. */
+  duplicates: number
+  /** Chunks that arrived ahead of a missing sequence number. */
+  reordered: number
+  /** Times missing sequence numbers were skipped. */
+  gaps: number
+  /** Sequence numbers skipped. */
+  lost: number
+  /** Chunks dropped because the buffer was full, or that failed the receiver. */
+  overflowed: number
+  /** Chunks held back waiting for a missing sequence number. */
+  held: number
+  /** Chunks waiting to be read. */
+  buffered: number
+}
+
+/** Options for `ctx.openStream`. */
+export interface StreamWriterOptions {
+  /** Metadata sent with every chunk. */
+  metadata?: Record<string, string>
+  /** Sequence number of the first chunk (default 0). */
+  startSequence?: number
+}
+
+/** Options applied when creating an ActrSystem. */
+export interface SystemOptions {
+  /** How `ActrId.serialNumber` is handed to JS by this system (default `BigInt`). */
+  serialNumberMode?: SerialNumberMode
+}
+
+/** Options for `ActrRef.watch`. */
+export interface WatchOptions {
+  /** Time between discovery rounds in ms (default 5000). */
+  intervalMs?: number
+  /** Number of candidates requested per round; defaults to `WorkloadOptions.discoveryCount`. */
+  count?: number
+}
+
+/** Options applied when attaching a workload. */
+export interface WorkloadOptions {
+  /** Maximum time `onStart` may take before the node fails to start (default 30000). */
+  startTimeoutMs?: number
+  /** Maximum time `onStop` may take before shutdown proceeds without it (default 30000). */
+  stopTimeoutMs?: number
+  /** How long discovered candidates are reused when calling by type (default 30000). */
+  targetCacheTtlMs?: number
+  /** Number of candidates requested per discovery when calling by type (default 8). */
+  discoveryCount?: number
+  /** Default strategy for picking a candidate when calling by type (default `RoundRobin`). */
+  loadBalance?: LoadBalanceStrategy
+  /** Ring points per actor for consistent hashing (default 160). */
+  virtualNodes?: number
+  /** Retry policy for outbound calls without a more specific one (default: no retries). */
+  retry?: RetryPolicy
+  /** Retry policies by route-key prefix; the longest matching prefix wins. */
+  retryRoutes?: Record<string, RetryPolicy>
+  /** Per-target circuit breakers for outbound calls (default: disabled). */
+  circuitBreaker?: CircuitBreakerOptions
+  /** Idempotent routes by route-key prefix, hedged as described by their policy. */
+  hedgeRoutes?: Record<string, HedgePolicy>
+  /** Route-key prefixes whose identical concurrent calls share one request. */
+  coalesceRoutes?: Array<string>
+  /** Client-side cache of responses of idempotent routes (default: disabled). */
+  responseCache?: ResponseCacheOptions
 }
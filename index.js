@@ -475,5 +475,21 @@ module.exports = nativeBinding
 module.exports.ActrNode = nativeBinding.ActrNode
 module.exports.ActrRef = nativeBinding.ActrRef
 module.exports.ActrSystem = nativeBinding.ActrSystem
+module.exports.actrIdEquals = nativeBinding.actrIdEquals
+module.exports.actrTypeEquals = nativeBinding.actrTypeEquals
+module.exports.CancellationToken = nativeBinding.CancellationToken
+module.exports.CircuitState = nativeBinding.CircuitState
 module.exports.ContextBridge = nativeBinding.ContextBridge
+module.exports.DiscoveryWatch = nativeBinding.DiscoveryWatch
+module.exports.formatActrId = nativeBinding.formatActrId
+module.exports.formatActrType = nativeBinding.formatActrType
+module.exports.hashActrId = nativeBinding.hashActrId
+module.exports.LoadBalanceStrategy = nativeBinding.LoadBalanceStrategy
+module.exports.parseActrId = nativeBinding.parseActrId
+module.exports.parseActrType = nativeBinding.parseActrType
 module.exports.PayloadType = nativeBinding.PayloadType
+module.exports.SerialNumberMode = nativeBinding.SerialNumberMode
+module.exports.StreamCloseReason = nativeBinding.StreamCloseReason
+module.exports.StreamOverflow = nativeBinding.StreamOverflow
+module.exports.StreamReceiver = nativeBinding.StreamReceiver
+module.exports.StreamWriter = nativeBinding.StreamWriter
@@ -1235,9 +1235,8 @@ function renderLocalActorTs(routeEntries, distImport) {
   lines.push('    throw new Error(`Unknown route: ${envelope.routeKey}`);');
   lines.push('  }');
   lines.push('');
-  lines.push('  const targetId = await ctx.discover(match.targetType);');
-  lines.push('  return await ctx.callRaw(');
-  lines.push('    targetId,');
+  lines.push('  return await ctx.callRawByType(');
+  lines.push('    match.targetType,');
   lines.push('    envelope.routeKey,');
   lines.push('    RPC_PAYLOAD_TYPE,');
   lines.push('    envelope.payload,');
@@ -1282,9 +1281,8 @@ function renderLocalActorJs(routeEntries, distImport) {
   lines.push('    throw new Error(`Unknown route: ${envelope.routeKey}`);');
   lines.push('  }');
   lines.push('');
-  lines.push('  const targetId = await ctx.discover(match.targetType);');
-  lines.push('  return await ctx.callRaw(');
-  lines.push('    targetId,');
+  lines.push('  return await ctx.callRawByType(');
+  lines.push('    match.targetType,');
   lines.push('    envelope.routeKey,');
   lines.push('    RPC_PAYLOAD_TYPE,');
   lines.push('    envelope.payload,');
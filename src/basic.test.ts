@@ -12,6 +12,28 @@ describe('ACTR Module Loading', () => {
     expect(actr.PayloadType).toBeDefined();
   });
 
+  it('should round-trip ActrId and ActrType strings', () => {
+    const { formatActrId, parseActrId, formatActrType, parseActrType, hashActrId } =
+      require('../index.js');
+    const id = parseActrId('18446744073709551615@1/acme+EchoService');
+    expect(id.serialNumber).toBe(18446744073709551615n);
+    expect(formatActrId(id)).toBe('18446744073709551615@1/acme+EchoService');
+    expect(formatActrType(parseActrType('acme+EchoService'))).toBe('acme+EchoService');
+    expect(hashActrId(id)).toBe(hashActrId(parseActrId(formatActrId(id))));
+    expect(() => parseActrType('EchoService')).toThrow(/INVALID_ARGUMENT/);
+  });
+
+  it('should hand out serial numbers as numbers in Number mode', () => {
+    const { formatActrId, parseActrId, hashActrId, SerialNumberMode } = require('../index.js');
+    const id = parseActrId('42@1/acme+EchoService', SerialNumberMode.Number);
+    expect(id.serialNumber).toBe(42);
+    expect(formatActrId(id)).toBe('42@1/acme+EchoService');
+    expect(hashActrId(id)).toBe(hashActrId(parseActrId('42@1/acme+EchoService')));
+    const large = parseActrId('18446744073709551615@1/acme+EchoService', SerialNumberMode.Number);
+    expect(typeof large.serialNumber).toBe('number');
+    expect(() => formatActrId(large)).toThrow(/INVALID_ARGUMENT/);
+  });
+
   it('should have correct PayloadType enum values', () => {
     const { PayloadType } = require('../index.js');
     expect(PayloadType.RpcReliable).toBe(0);
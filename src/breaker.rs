@@ -0,0 +1,448 @@
+// Per-target circuit breakers
+//
+// Each target (or target + route key with `perRoute`) has a circuit that opens after
+// `failureThreshold` consecutive failures. While open, calls to the target reject right away with
+// `CIRCUIT_OPEN` instead of waiting for their timeout, and load balancing skips it. After `openMs`
+// the circuit lets `halfOpenProbes` calls through; `successThreshold` successful probes close it
+// again and a failed probe re-opens it.
+//
+// Only errors in `failOn` count as failures; unknown codes are rejected when the node is attached. Any other outcome, including an application error,
+// shows the target is responding; aborted calls are not counted either way.
+
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::{ErrorCode, actr_error};
+use crate::types::{ActrId, SerialNumberMode};
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+const DEFAULT_OPEN_MS: u32 = 30_000;
+const DEFAULT_HALF_OPEN_PROBES: u32 = 1;
+const DEFAULT_SUCCESS_THRESHOLD: u32 = 1;
+const DEFAULT_FAIL_ON: [ErrorCode; 3] = [
+    ErrorCode::Timeout,
+    ErrorCode::Unavailable,
+    ErrorCode::Transport,
+];
+
+/// Circuit breaker settings for outbound calls.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct CircuitBreakerOptions {
+    /// Consecutive failures that open a circuit (default 5).
+    #[napi(js_name = "failureThreshold")]
+    pub failure_threshold: Option<u32>,
+    /// Time a circuit stays open before probing the target, in ms (default 30000).
+    #[napi(js_name = "openMs")]
+    pub open_ms: Option<u32>,
+    /// Calls let through at once while half-open (default 1).
+    #[napi(js_name = "halfOpenProbes")]
+    pub half_open_probes: Option<u32>,
+    /// Successful probes that close a half-open circuit (default 1).
+    #[napi(js_name = "successThreshold")]
+    pub success_threshold: Option<u32>,
+    /// Keep a separate circuit per route key of each target (default false).
+    #[napi(js_name = "perRoute")]
+    pub per_route: Option<bool>,
+    /// Error codes counted as failures (default `TIMEOUT`, `UNAVAILABLE` and `TRANSPORT`).
+    #[napi(js_name = "failOn")]
+    pub fail_on: Option<Vec<String>>,
+}
+
+/// State of a circuit breaker.
+#[napi]
+#[derive(PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls pass through.
+    Closed,
+    /// Calls are rejected with `CIRCUIT_OPEN`.
+    Open,
+    /// A limited number of probe calls pass through.
+    HalfOpen,
+}
+
+/// State of one circuit, as reported by `ActrRef.circuitStates`.
+#[napi(object)]
+pub struct CircuitStatus {
+    pub target: ActrId,
+    /// Set when circuits are kept per route.
+    #[napi(js_name = "routeKey")]
+    pub route_key: Option<String>,
+    pub state: CircuitState,
+    /// Failures since the last success.
+    #[napi(js_name = "consecutiveFailures")]
+    pub consecutive_failures: u32,
+    /// When an open circuit starts probing, in ms since the Unix epoch.
+    #[napi(js_name = "retryAtMs")]
+    pub retry_at_ms: Option<f64>,
+}
+
+struct Circuit {
+    target: actr_protocol::ActrId,
+    route_key: Option<String>,
+    state: CircuitState,
+    failures: u32,
+    opened_at: Instant,
+    probes: u32,
+    successes: u32,
+}
+
+impl Circuit {
+    /// Move an open circuit to half-open once its cool-down has passed.
+    fn refresh(&mut self, open_for: Duration) {
+        if self.state == CircuitState::Open && self.opened_at.elapsed() >= open_for {
+            self.state = CircuitState::HalfOpen;
+            self.probes = 0;
+            self.successes = 0;
+        }
+    }
+
+    fn open(&mut self) {
+        self.state = CircuitState::Open;
+        self.opened_at = Instant::now();
+        self.probes = 0;
+        self.successes = 0;
+    }
+}
+
+/// The circuits of one node.
+pub struct CircuitBreakers {
+    failure_threshold: u32,
+    open_for: Duration,
+    half_open_probes: u32,
+    success_threshold: u32,
+    per_route: bool,
+    fail_on: Vec<ErrorCode>,
+    circuits: Mutex<HashMap<String, Circuit>>,
+}
+
+impl CircuitBreakers {
+    /// Apply the defaults of `options`, rejecting unknown `failOn` codes.
+    pub fn new(options: &CircuitBreakerOptions) -> napi::Result<Self> {
+        let fail_on = match &options.fail_on {
+            Some(codes) => codes
+                .iter()
+                .map(|code| {
+                    ErrorCode::parse(code).ok_or_else(|| {
+                        actr_error(
+                            ErrorCode::InvalidArgument,
+                            format!("unknown error code {code:?} in failOn"),
+                        )
+                    })
+                })
+                .collect::<napi::Result<_>>()?,
+            None => DEFAULT_FAIL_ON.to_vec(),
+        };
+        Ok(Self {
+            failure_threshold: options
+                .failure_threshold
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD)
+                .max(1),
+            open_for: Duration::from_millis(options.open_ms.unwrap_or(DEFAULT_OPEN_MS) as u64),
+            half_open_probes: options
+                .half_open_probes
+                .unwrap_or(DEFAULT_HALF_OPEN_PROBES)
+                .max(1),
+            success_threshold: options
+                .success_threshold
+                .unwrap_or(DEFAULT_SUCCESS_THRESHOLD)
+                .max(1),
+            per_route: options.per_route.unwrap_or(false),
+            fail_on,
+            circuits: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn key(&self, target: &actr_protocol::ActrId, route_key: Option<&str>) -> String {
+        let id = crate::identity::id_to_string(target);
+        match route_key.filter(|_| self.per_route) {
+            Some(route_key) => format!("{id} {route_key}"),
+            None => id,
+        }
+    }
+
+    /// Whether a call to `target` would currently be let through. Without a route key and with
+    /// per-route circuits, only a target whose every circuit is open is refused.
+    pub fn admits(&self, target: &actr_protocol::ActrId, route_key: Option<&str>) -> bool {
+        let mut circuits = self.circuits.lock();
+        let admits = |circuit: &mut Circuit| {
+            circuit.refresh(self.open_for);
+            match circuit.state {
+                CircuitState::Closed => true,
+                CircuitState::Open => false,
+                CircuitState::HalfOpen => circuit.probes < self.half_open_probes,
+            }
+        };
+        if route_key.is_none() && self.per_route {
+            let mut circuits = circuits
+                .values_mut()
+                .filter(|circuit| circuit.target == *target)
+                .peekable();
+            return circuits.peek().is_none() || circuits.any(admits);
+        }
+        circuits
+            .get_mut(&self.key(target, route_key))
+            .is_none_or(admits)
+    }
+
+    /// Let a call to `target` through, or reject it with `CIRCUIT_OPEN`.
+    pub fn acquire(
+        &self,
+        target: &actr_protocol::ActrId,
+        route_key: &str,
+    ) -> napi::Result<BreakerPermit<'_>> {
+        let key = self.key(target, Some(route_key));
+        let mut circuits = self.circuits.lock();
+        let circuit = circuits.entry(key.clone()).or_insert_with(|| Circuit {
+            target: target.clone(),
+            route_key: self.per_route.then(|| route_key.to_string()),
+            state: CircuitState::Closed,
+            failures: 0,
+            opened_at: Instant::now(),
+            probes: 0,
+            successes: 0,
+        });
+        circuit.refresh(self.open_for);
+        let probe = match circuit.state {
+            CircuitState::Closed => false,
+            CircuitState::HalfOpen if circuit.probes < self.half_open_probes => {
+                circuit.probes += 1;
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => {
+                let retry_in = self.open_for.saturating_sub(circuit.opened_at.elapsed());
+                return Err(actr_error(
+                    ErrorCode::CircuitOpen,
+                    format!(
+                        "circuit for {key} is open; retry in {}ms",
+                        retry_in.as_millis()
+                    ),
+                ));
+            }
+        };
+        Ok(BreakerPermit {
+            breakers: self,
+            key,
+            probe,
+        })
+    }
+
+    /// Current state of every circuit, with targets in `serial_numbers` mode.
+    pub fn statuses(&self, serial_numbers: SerialNumberMode) -> Vec<CircuitStatus> {
+        let mut circuits = self.circuits.lock();
+        circuits
+            .values_mut()
+            .map(|circuit| {
+                circuit.refresh(self.open_for);
+                let retry_at_ms = (circuit.state == CircuitState::Open).then(|| {
+                    let retry_in = self.open_for.saturating_sub(circuit.opened_at.elapsed());
+                    (SystemTime::now() + retry_in)
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_millis() as f64
+                });
+                CircuitStatus {
+                    target: serial_numbers.id_to_js(circuit.target.clone()),
+                    route_key: circuit.route_key.clone(),
+                    state: circuit.state,
+                    consecutive_failures: circuit.failures,
+                    retry_at_ms,
+                }
+            })
+            .collect()
+    }
+
+    /// Close every circuit of `target`, or every circuit when `target` is `None`.
+    pub fn reset(&self, target: Option<&actr_protocol::ActrId>) {
+        self.circuits
+            .lock()
+            .retain(|_, circuit| target.is_some_and(|target| circuit.target != *target));
+    }
+
+    fn is_failure(&self, e: &napi::Error) -> bool {
+        self.fail_on
+            .iter()
+            .any(|code| crate::error::has_code(e, *code))
+    }
+}
+
+/// A call let through by `CircuitBreakers::acquire`; report its outcome with `record`.
+///
+/// Dropping the permit without recording releases it without changing the circuit.
+pub struct BreakerPermit<'a> {
+    breakers: &'a CircuitBreakers,
+    key: String,
+    probe: bool,
+}
+
+impl BreakerPermit<'_> {
+    /// Update the circuit with the outcome of the call.
+    pub fn record<T>(mut self, result: &napi::Result<T>) {
+        let breakers = self.breakers;
+        let failed = match result {
+            Ok(_) => false,
+            Err(e) if crate::error::has_code(e, ErrorCode::Aborted) => return,
+            Err(e) => breakers.is_failure(e),
+        };
+
+        let mut circuits = breakers.circuits.lock();
+        let Some(circuit) = circuits.get_mut(&self.key) else {
+            return;
+        };
+        if self.probe {
+            self.probe = false;
+            circuit.probes = circuit.probes.saturating_sub(1);
+        }
+        match (&circuit.state, failed) {
+            (CircuitState::HalfOpen, true) => {
+                circuit.failures += 1;
+                circuit.open();
+                tracing::debug!("circuit for {} re-opened after a failed probe", self.key);
+            }
+            (CircuitState::HalfOpen, false) => {
+                circuit.successes += 1;
+                if circuit.successes >= breakers.success_threshold {
+                    circuit.state = CircuitState::Closed;
+                    circuit.failures = 0;
+                    tracing::debug!("circuit for {} closed", self.key);
+                }
+            }
+            (_, true) => {
+                circuit.failures += 1;
+                if circuit.state == CircuitState::Closed
+                    && circuit.failures >= breakers.failure_threshold
+                {
+                    circuit.open();
+                    tracing::debug!(
+                        "circuit for {} opened after {} failures",
+                        self.key,
+                        circuit.failures
+                    );
+                }
+            }
+            (_, false) => circuit.failures = 0,
+        }
+    }
+}
+
+impl Drop for BreakerPermit<'_> {
+    fn drop(&mut self) {
+        if self.probe
+            && let Some(circuit) = self.breakers.circuits.lock().get_mut(&self.key)
+        {
+            circuit.probes = circuit.probes.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> actr_protocol::ActrId {
+        crate::identity::parse_id("1@1/acme+echo").unwrap()
+    }
+
+    fn breakers(open_ms: u32) -> CircuitBreakers {
+        CircuitBreakers::new(&CircuitBreakerOptions {
+            failure_threshold: Some(2),
+            open_ms: Some(open_ms),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn call(breakers: &CircuitBreakers, code: Option<ErrorCode>) -> napi::Result<()> {
+        let permit = breakers.acquire(&target(), "echo.Echo")?;
+        permit.record(&code.map_or(Ok(()), |code| Err(actr_error(code, "failed"))));
+        Ok(())
+    }
+
+    fn state(breakers: &CircuitBreakers) -> CircuitState {
+        breakers.statuses(SerialNumberMode::BigInt).remove(0).state
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breakers = breakers(60_000);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        assert!(state(&breakers) == CircuitState::Closed);
+        call(&breakers, Some(ErrorCode::Unavailable)).unwrap();
+        assert!(state(&breakers) == CircuitState::Open);
+        assert!(!breakers.admits(&target(), None));
+
+        let e = call(&breakers, None).unwrap_err();
+        assert!(crate::error::has_code(&e, ErrorCode::CircuitOpen));
+    }
+
+    #[test]
+    fn other_outcomes_reset_the_failure_count() {
+        let breakers = breakers(60_000);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, Some(ErrorCode::Application)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        assert!(state(&breakers) == CircuitState::Closed);
+
+        call(&breakers, Some(ErrorCode::Aborted)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        assert!(state(&breakers) == CircuitState::Open);
+    }
+
+    #[test]
+    fn half_open_circuit_limits_probes() {
+        let breakers = breakers(0);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        assert!(state(&breakers) == CircuitState::HalfOpen);
+
+        let probe = breakers.acquire(&target(), "echo.Echo").unwrap();
+        let e = breakers.acquire(&target(), "echo.Echo").err().unwrap();
+        assert!(crate::error::has_code(&e, ErrorCode::CircuitOpen));
+        drop(probe);
+        assert!(breakers.admits(&target(), Some("echo.Echo")));
+    }
+
+    #[test]
+    fn successful_probe_closes() {
+        let breakers = breakers(0);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, None).unwrap();
+        assert!(state(&breakers) == CircuitState::Closed);
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let breakers = breakers(60_000);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        breakers.circuits.lock().values_mut().for_each(|circuit| {
+            circuit.opened_at -= Duration::from_secs(60);
+        });
+        call(&breakers, Some(ErrorCode::Transport)).unwrap();
+        assert!(state(&breakers) == CircuitState::Open);
+    }
+
+    #[test]
+    fn reset_closes_circuits() {
+        let breakers = breakers(60_000);
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        call(&breakers, Some(ErrorCode::Timeout)).unwrap();
+        breakers.reset(Some(&target()));
+        assert!(breakers.statuses(SerialNumberMode::BigInt).is_empty());
+        call(&breakers, None).unwrap();
+    }
+
+    #[test]
+    fn rejects_unknown_fail_on_codes() {
+        let e = CircuitBreakers::new(&CircuitBreakerOptions {
+            fail_on: Some(vec!["TIMEOUT".into(), "TIMEOTU".into()]),
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert!(crate::error::has_code(&e, ErrorCode::InvalidArgument));
+    }
+}
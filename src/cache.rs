@@ -0,0 +1,388 @@
+// Client-side response cache
+//
+// Responses of routes listed in `WorkloadOptions.responseCache.routes` are kept for the route's
+// TTL, keyed by destination, route key and the full request (payload type, metadata and
+// payload), so distinct requests never share a response. The cache holds at most `maxBytes`,
+// counting keys as well as responses, and evicts the least recently used responses first;
+// expired responses are dropped when next looked up or evicted. Errors are never cached, and
+// neither is a response fetched while the cache was invalidated, since it may predate the change
+// that prompted the invalidation.
+
+use bytes::Bytes;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::prefixes::RoutePrefixes;
+
+/// Default memory bound of the response cache, in bytes.
+const DEFAULT_MAX_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Bookkeeping charged per cached response on top of its key and payload, in bytes.
+const ENTRY_OVERHEAD: usize = 64;
+
+/// Settings of the node's response cache.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ResponseCacheOptions {
+    /// Cached routes by route-key prefix, with how long a response stays valid in ms.
+    pub routes: HashMap<String, u32>,
+    /// Memory bound of the cache in bytes, requests and responses together (default 16 MiB).
+    #[napi(js_name = "maxBytes")]
+    pub max_bytes: Option<u32>,
+}
+
+/// Counters of the response cache, as reported by `ActrRef.cacheStats`.
+#[napi(object)]
+pub struct CacheStats {
+    pub hits: i64,
+    pub misses: i64,
+    /// Responses dropped to stay within `maxBytes`.
+    pub evictions: i64,
+    pub entries: u32,
+    pub bytes: i64,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    dest: String,
+    route_key: String,
+    payload_type: i32,
+    metadata: Vec<(String, String)>,
+    payload: Bytes,
+}
+
+impl CacheKey {
+    pub fn new(
+        dest: String,
+        route_key: &str,
+        payload_type: i32,
+        metadata: &[(String, String)],
+        payload: &Bytes,
+    ) -> Self {
+        Self {
+            dest,
+            route_key: route_key.to_string(),
+            payload_type,
+            metadata: metadata.to_vec(),
+            payload: payload.clone(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let metadata: usize = self.metadata.iter().map(|(k, v)| k.len() + v.len()).sum();
+        self.dest.len() + self.route_key.len() + metadata + self.payload.len() + ENTRY_OVERHEAD
+    }
+}
+
+struct Entry {
+    response: Bytes,
+    expires_at: Instant,
+    /// Position in `State::recency`.
+    used: u64,
+}
+
+#[derive(Default)]
+struct State {
+    entries: HashMap<CacheKey, Entry>,
+    /// Keys by last use, oldest first.
+    recency: BTreeMap<u64, CacheKey>,
+    clock: u64,
+    /// Bumped by every invalidation.
+    generation: u64,
+    bytes: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl State {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.used);
+            self.bytes -= key.size() + entry.response.len();
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+/// Cached responses of a node.
+pub struct ResponseCache {
+    routes: RoutePrefixes<Duration>,
+    max_bytes: usize,
+    state: Mutex<State>,
+}
+
+impl ResponseCache {
+    pub fn new(options: Option<&ResponseCacheOptions>) -> Self {
+        let routes = options
+            .into_iter()
+            .flat_map(|options| &options.routes)
+            .map(|(prefix, ttl_ms)| (prefix.clone(), Duration::from_millis(*ttl_ms as u64)))
+            .collect();
+        Self {
+            routes,
+            max_bytes: options
+                .and_then(|options| options.max_bytes)
+                .unwrap_or(DEFAULT_MAX_BYTES) as usize,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// How long a response of `route_key` is cached, or `None` when it is not. `use_cache:
+    /// false` bypasses the cache for one call.
+    pub fn ttl(&self, route_key: &str, use_cache: Option<bool>) -> Option<Duration> {
+        if use_cache == Some(false) {
+            return None;
+        }
+        self.routes
+            .find(route_key)
+            .copied()
+            .filter(|ttl| !ttl.is_zero())
+    }
+
+    /// Answer from the cache, or run `fetch` and cache its response for `ttl`.
+    pub async fn get_or_fetch<Fut>(
+        &self,
+        key: CacheKey,
+        ttl: Duration,
+        fetch: impl FnOnce() -> Fut,
+    ) -> napi::Result<Bytes>
+    where
+        Fut: Future<Output = napi::Result<Bytes>>,
+    {
+        if let Some(response) = self.get(&key) {
+            return Ok(response);
+        }
+        let generation = self.state.lock().generation;
+        let response = fetch().await?;
+        self.insert(key, response.clone(), ttl, generation);
+        Ok(response)
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        let mut state = self.state.lock();
+        let Some(entry) = state.entries.get(key) else {
+            state.misses += 1;
+            return None;
+        };
+        if entry.expires_at <= Instant::now() {
+            state.remove(key);
+            state.misses += 1;
+            return None;
+        }
+
+        let used = entry.used;
+        let response = entry.response.clone();
+        let tick = state.tick();
+        state.recency.remove(&used);
+        state.recency.insert(tick, key.clone());
+        if let Some(entry) = state.entries.get_mut(key) {
+            entry.used = tick;
+        }
+        state.hits += 1;
+        Some(response)
+    }
+
+    /// Cache `response` unless the cache was invalidated since `generation` was read.
+    fn insert(&self, key: CacheKey, response: Bytes, ttl: Duration, generation: u64) {
+        let size = key.size() + response.len();
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.remove(&key);
+        while state.bytes + size > self.max_bytes {
+            let Some(oldest) = state.recency.values().next().cloned() else {
+                break;
+            };
+            state.remove(&oldest);
+            state.evictions += 1;
+        }
+        let tick = state.tick();
+        state.recency.insert(tick, key.clone());
+        state.bytes += size;
+        state.entries.insert(
+            key,
+            Entry {
+                response,
+                expires_at: Instant::now() + ttl,
+                used: tick,
+            },
+        );
+    }
+
+    /// Drop the cached responses of routes starting with `route_prefix`, or all of them.
+    pub fn invalidate(&self, route_prefix: Option<&str>) {
+        let mut state = self.state.lock();
+        state.generation += 1;
+        let stale: Vec<CacheKey> = state
+            .entries
+            .keys()
+            .filter(|key| route_prefix.is_none_or(|prefix| key.route_key.starts_with(prefix)))
+            .cloned()
+            .collect();
+        for key in &stale {
+            state.remove(key);
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock();
+        CacheStats {
+            hits: state.hits as i64,
+            misses: state.misses as i64,
+            evictions: state.evictions as i64,
+            entries: state.entries.len() as u32,
+            bytes: state.bytes as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn cache(max_bytes: u32) -> ResponseCache {
+        ResponseCache::new(Some(&ResponseCacheOptions {
+            routes: HashMap::from([("cache.".to_string(), 60_000), ("cache.Now".to_string(), 0)]),
+            max_bytes: Some(max_bytes),
+        }))
+    }
+
+    fn key(payload: &'static [u8]) -> CacheKey {
+        CacheKey::new(
+            "1@1/acme+cache".to_string(),
+            "cache.Get",
+            0,
+            &[],
+            &Bytes::from_static(payload),
+        )
+    }
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn insert(cache: &ResponseCache, key: CacheKey, response: &'static [u8], ttl: Duration) {
+        let generation = cache.state.lock().generation;
+        cache.insert(key, Bytes::from_static(response), ttl, generation);
+    }
+
+    #[test]
+    fn caches_only_listed_routes() {
+        let cache = cache(1024);
+        assert_eq!(cache.ttl("cache.Get", None), Some(TTL));
+        assert_eq!(cache.ttl("cache.Get", Some(false)), None);
+        assert_eq!(cache.ttl("cache.Now", None), None);
+        assert_eq!(cache.ttl("echo.Echo", None), None);
+    }
+
+    #[tokio::test]
+    async fn answers_identical_requests_from_the_cache() {
+        let cache = cache(1024);
+        for _ in 0..2 {
+            let response = cache
+                .get_or_fetch(key(b"a"), TTL, || async { Ok(Bytes::from_static(b"1")) })
+                .await;
+            assert_eq!(response.unwrap(), Bytes::from_static(b"1"));
+        }
+        let response = cache
+            .get_or_fetch(key(b"b"), TTL, || async { Ok(Bytes::from_static(b"2")) })
+            .await;
+        assert_eq!(response.unwrap(), Bytes::from_static(b"2"));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+    }
+
+    #[tokio::test]
+    async fn never_caches_errors() {
+        let cache = cache(1024);
+        let failed = cache
+            .get_or_fetch(key(b"a"), TTL, || async {
+                Err(crate::error::actr_error(ErrorCode::Unavailable, "down"))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn counts_requests_and_responses_in_the_size() {
+        let cache = cache(1024);
+        let key = key(b"request");
+        let size = key.size() + 8;
+        assert!(size > "1@1/acme+cache".len() + "cache.Get".len() + "request".len());
+        insert(&cache, key, b"response", TTL);
+        assert_eq!(cache.stats().bytes, size as i64);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_response() {
+        let entry = key(b"a").size() + 1;
+        let cache = cache((entry * 2) as u32);
+        insert(&cache, key(b"a"), b"1", TTL);
+        insert(&cache, key(b"b"), b"2", TTL);
+        assert!(cache.get(&key(b"a")).is_some());
+        insert(&cache, key(b"c"), b"3", TTL);
+
+        assert!(cache.get(&key(b"b")).is_none());
+        assert!(cache.get(&key(b"a")).is_some());
+        assert!(cache.get(&key(b"c")).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn skips_responses_larger_than_the_cache() {
+        let cache = cache(16);
+        insert(&cache, key(b"a"), b"1", TTL);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn drops_expired_responses() {
+        let cache = cache(1024);
+        insert(&cache, key(b"a"), b"1", Duration::ZERO);
+        assert!(cache.get(&key(b"a")).is_none());
+        assert_eq!(cache.stats().bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn skips_responses_fetched_across_an_invalidation() {
+        let cache = cache(1024);
+        let response = cache
+            .get_or_fetch(key(b"a"), TTL, || async {
+                cache.invalidate(Some("cache."));
+                Ok(Bytes::from_static(b"stale"))
+            })
+            .await;
+        assert_eq!(response.unwrap(), Bytes::from_static(b"stale"));
+        assert_eq!(cache.stats().entries, 0);
+
+        let response = cache
+            .get_or_fetch(key(b"a"), TTL, || async { Ok(Bytes::from_static(b"1")) })
+            .await;
+        assert_eq!(response.unwrap(), Bytes::from_static(b"1"));
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn invalidates_by_route_prefix() {
+        let cache = cache(1024);
+        insert(&cache, key(b"a"), b"1", TTL);
+        cache.invalidate(Some("echo."));
+        assert_eq!(cache.stats().entries, 1);
+        cache.invalidate(Some("cache."));
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -0,0 +1,248 @@
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Weak};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::error::{ErrorCode, actr_error};
+
+/// Reserved route key used to tell a remote actr-ts node that a call was cancelled.
+/// The payload is the `actr-call-id` metadata value of the cancelled call.
+pub const CANCEL_ROUTE_KEY: &str = "actr.Cancel";
+
+/// Reserved metadata key identifying a cancellable call on the remote side.
+pub const CALL_ID_KEY: &str = "actr-call-id";
+
+static NEXT_TOKEN_ID: AtomicU64 = AtomicU64::new(1);
+static NEXT_CALL_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Live tokens, looked up by id when a call is made with `CallOptions.cancelTokenId`. Ids are
+/// never reused, so a stale id cannot reach another token.
+static TOKENS: LazyLock<Mutex<HashMap<u64, watch::Receiver<bool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Requests being handled by this node, keyed by (caller, `actr-call-id`).
+static IN_FLIGHT: LazyLock<Mutex<HashMap<(String, String), Weak<RequestScope>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Native cancellation token.
+///
+/// The TypeScript layer creates one per `AbortSignal` and passes its `id` as
+/// `CallOptions.cancelTokenId`; calling `cancel()` rejects every call using it with `ABORTED`.
+#[napi]
+pub struct CancellationToken {
+    id: u64,
+    tx: watch::Sender<bool>,
+}
+
+#[napi]
+impl CancellationToken {
+    #[napi(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let id = NEXT_TOKEN_ID.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = watch::channel(false);
+        TOKENS.lock().insert(id, rx);
+        Self { id, tx }
+    }
+
+    /// Identifier passed as `CallOptions.cancelTokenId`.
+    #[napi(getter)]
+    pub fn id(&self) -> i64 {
+        self.id as i64
+    }
+
+    /// Cancel every call using this token.
+    #[napi]
+    pub fn cancel(&self) {
+        self.tx.send_replace(true);
+    }
+
+    /// Whether `cancel()` has been called.
+    #[napi(getter)]
+    pub fn is_cancelled(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Unregister the token once no call will use it; its id is rejected afterwards. Calls
+    /// already using it can still be cancelled.
+    #[napi]
+    pub fn dispose(&self) {
+        TOKENS.lock().remove(&self.id);
+    }
+}
+
+/// Runs when the JS token is garbage-collected, so tokens that are never disposed do not stay
+/// registered.
+impl Drop for CancellationToken {
+    fn drop(&mut self) {
+        TOKENS.lock().remove(&self.id);
+    }
+}
+
+/// Resolve a token id into a receiver; unknown ids are rejected.
+pub fn lookup(token_id: Option<i64>) -> napi::Result<Option<watch::Receiver<bool>>> {
+    token_id
+        .map(|id| {
+            let token = u64::try_from(id)
+                .ok()
+                .and_then(|id| TOKENS.lock().get(&id).cloned());
+            token.ok_or_else(|| {
+                actr_error(
+                    ErrorCode::InvalidArgument,
+                    format!("unknown cancellation token {id}"),
+                )
+            })
+        })
+        .transpose()
+}
+
+/// Resolve once the token is cancelled. Never resolves if the token is dropped first.
+pub async fn cancelled(mut rx: watch::Receiver<bool>) {
+    if rx.wait_for(|cancelled| *cancelled).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Allocate an id for a cancellable outbound call.
+pub fn next_call_id() -> String {
+    let pid = std::process::id();
+    let seq = NEXT_CALL_ID.fetch_add(1, Ordering::Relaxed);
+    format!("{pid:x}-{seq:x}")
+}
+
+/// Run `fut`, rejecting with `ABORTED` if the token fires first. The future is dropped on abort.
+pub async fn with_cancel<T>(
+    rx: Option<watch::Receiver<bool>>,
+    fut: impl Future<Output = napi::Result<T>>,
+) -> napi::Result<T> {
+    let Some(rx) = rx else {
+        return fut.await;
+    };
+    if *rx.borrow() {
+        return Err(actr_error(ErrorCode::Aborted, "call aborted"));
+    }
+
+    tokio::select! {
+        result = fut => result,
+        _ = cancelled(rx) => Err(actr_error(ErrorCode::Aborted, "call aborted")),
+    }
+}
+
+/// Cancellation and deadline state of one incoming request.
+///
+/// Fires when the caller sends a cancellation notice or the caller's deadline passes, and is
+/// finished by the dispatcher once the handler returns.
+pub struct RequestScope {
+    deadline: Option<Instant>,
+    deadline_ms: Option<f64>,
+    cancel_tx: watch::Sender<bool>,
+    done_tx: watch::Sender<bool>,
+    key: Option<(String, String)>,
+}
+
+impl RequestScope {
+    /// Start tracking a request. `deadline_ms` is the absolute deadline in ms since Unix epoch.
+    pub fn begin(
+        caller: Option<&actr_protocol::ActrId>,
+        call_id: Option<String>,
+        timeout_ms: Option<i64>,
+        deadline_ms: Option<f64>,
+    ) -> Arc<Self> {
+        let key = call_id.map(|call_id| (caller_key(caller), call_id));
+        let scope = Arc::new(Self {
+            deadline: timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms as u64)),
+            deadline_ms,
+            cancel_tx: watch::channel(false).0,
+            done_tx: watch::channel(false).0,
+            key,
+        });
+        if let Some(key) = &scope.key {
+            IN_FLIGHT.lock().insert(key.clone(), Arc::downgrade(&scope));
+        }
+        scope
+    }
+
+    /// Absolute deadline in ms since Unix epoch, if the caller set a timeout.
+    pub fn deadline_ms(&self) -> Option<f64> {
+        self.deadline_ms
+    }
+
+    /// Time left before the deadline; zero once it has passed.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Receiver that flips to `true` when the caller cancels.
+    pub fn cancel_rx(&self) -> watch::Receiver<bool> {
+        self.cancel_tx.subscribe()
+    }
+
+    pub fn cancel(&self) {
+        self.cancel_tx.send_replace(true);
+    }
+
+    /// Resolve `true` when the request is cancelled or its deadline passes, or `false` once the
+    /// handler has finished.
+    pub async fn wait(&self) -> bool {
+        let mut cancel_rx = self.cancel_tx.subscribe();
+        let mut done_rx = self.done_tx.subscribe();
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            _ = done_rx.wait_for(|done| *done) => false,
+            _ = cancel_rx.wait_for(|cancelled| *cancelled) => true,
+            _ = deadline => true,
+        }
+    }
+
+    /// Guard that finishes the request when dropped, so it is finished however dispatch ends.
+    pub fn finish_on_drop(self: &Arc<Self>) -> FinishGuard {
+        FinishGuard(self.clone())
+    }
+
+    /// Mark the request as handled and stop accepting cancellation notices for it.
+    fn finish(&self) {
+        self.done_tx.send_replace(true);
+        if let Some(key) = &self.key {
+            IN_FLIGHT.lock().remove(key);
+        }
+    }
+}
+
+/// Finishes its request when dropped; see `RequestScope::finish_on_drop`.
+pub struct FinishGuard(Arc<RequestScope>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+fn caller_key(caller: Option<&actr_protocol::ActrId>) -> String {
+    caller
+        .map(crate::identity::id_to_string)
+        .unwrap_or_default()
+}
+
+/// Handle a cancellation notice sent by `caller` for call `call_id`.
+pub fn cancel_request(caller: Option<&actr_protocol::ActrId>, call_id: &str) {
+    let scope = IN_FLIGHT
+        .lock()
+        .get(&(caller_key(caller), call_id.to_string()))
+        .and_then(Weak::upgrade);
+    if let Some(scope) = scope {
+        scope.cancel();
+    }
+}
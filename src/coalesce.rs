@@ -0,0 +1,308 @@
+// Singleflight for outbound calls
+//
+// Identical calls that opt in (same destination, route key, payload type, metadata and payload
+// bytes) and overlap in time share one request: the first caller sends it and the others wait for
+// its outcome. If the sending caller is cancelled or drops its call, a waiting caller takes over
+// and sends the request itself.
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio::sync::watch;
+
+use crate::error::ErrorCode;
+
+/// What makes two calls identical.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct CallKey {
+    /// Canonical target id, or target type (plus shard key) for calls by type.
+    pub dest: String,
+    pub route_key: String,
+    pub payload_type: i32,
+    pub metadata: Vec<(String, String)>,
+    pub payload: Bytes,
+}
+
+#[derive(Clone)]
+enum Outcome {
+    /// The response, or the reason of the error the call failed with.
+    Done(std::result::Result<Bytes, String>),
+    /// The sending caller went away; another caller has to send the request.
+    Abandoned,
+}
+
+enum Role {
+    /// First caller: send the request and publish its outcome.
+    Send(watch::Sender<Option<Outcome>>),
+    /// An identical call is in flight: wait for its outcome.
+    Wait(watch::Receiver<Option<Outcome>>),
+}
+
+/// In-flight coalesced calls of a node.
+#[derive(Default)]
+pub struct Coalescer {
+    /// Route-key prefixes coalesced without `CallOptions.coalesce`.
+    routes: Vec<String>,
+    flights: Mutex<HashMap<CallKey, watch::Receiver<Option<Outcome>>>>,
+}
+
+impl Coalescer {
+    pub fn new(routes: Option<Vec<String>>) -> Self {
+        Self {
+            routes: routes.unwrap_or_default(),
+            flights: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a call to `route_key` is coalesced; `per_call` takes precedence.
+    pub fn applies(&self, route_key: &str, per_call: Option<bool>) -> bool {
+        per_call.unwrap_or_else(|| {
+            self.routes
+                .iter()
+                .any(|prefix| route_key.starts_with(prefix.as_str()))
+        })
+    }
+
+    /// Send the call through `send`, unless an identical call is already in flight, in which
+    /// case wait for and share its outcome.
+    ///
+    /// Waiting ends early with `ABORTED` when either cancellation receiver fires.
+    pub async fn run<F, Fut>(
+        &self,
+        key: CallKey,
+        cancel: (Option<watch::Receiver<bool>>, Option<watch::Receiver<bool>>),
+        send: F,
+    ) -> napi::Result<Bytes>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = napi::Result<Bytes>>,
+    {
+        loop {
+            let role = {
+                let mut flights = self.flights.lock();
+                match flights.get(&key) {
+                    Some(rx) => Role::Wait(rx.clone()),
+                    None => {
+                        let (tx, rx) = watch::channel(None);
+                        flights.insert(key.clone(), rx);
+                        Role::Send(tx)
+                    }
+                }
+            };
+            let mut rx = match role {
+                Role::Wait(rx) => rx,
+                Role::Send(tx) => {
+                    let flight = Flight {
+                        coalescer: self,
+                        key: &key,
+                        tx,
+                        finished: false,
+                    };
+                    let result = send().await;
+                    flight.finish(&result);
+                    return result;
+                }
+            };
+
+            let outcome = crate::cancel::with_cancel(
+                cancel.0.clone(),
+                crate::cancel::with_cancel(cancel.1.clone(), async {
+                    Ok(rx
+                        .wait_for(Option::is_some)
+                        .await
+                        .ok()
+                        .and_then(|outcome| outcome.clone()))
+                }),
+            )
+            .await?;
+            match outcome {
+                Some(Outcome::Done(Ok(response))) => return Ok(response),
+                Some(Outcome::Done(Err(reason))) => return Err(napi::Error::from_reason(reason)),
+                Some(Outcome::Abandoned) | None => {}
+            }
+        }
+    }
+}
+
+/// A call being sent on behalf of every identical caller.
+struct Flight<'a> {
+    coalescer: &'a Coalescer,
+    key: &'a CallKey,
+    tx: watch::Sender<Option<Outcome>>,
+    finished: bool,
+}
+
+impl Flight<'_> {
+    fn finish(mut self, result: &napi::Result<Bytes>) {
+        let outcome = match result {
+            Ok(response) => Outcome::Done(Ok(response.clone())),
+            // Cancelled by its own caller; the others still want a response.
+            Err(e) if crate::error::has_code(e, ErrorCode::Aborted) => Outcome::Abandoned,
+            Err(e) => Outcome::Done(Err(e.reason.clone())),
+        };
+        self.finished = true;
+        self.complete(outcome);
+    }
+
+    fn complete(&self, outcome: Outcome) {
+        self.coalescer.flights.lock().remove(self.key);
+        self.tx.send_replace(Some(outcome));
+    }
+}
+
+impl Drop for Flight<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.complete(Outcome::Abandoned);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::{join, join3};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn key() -> CallKey {
+        CallKey {
+            dest: "1@1/acme+cache".to_string(),
+            route_key: "cache.Get".to_string(),
+            payload_type: 0,
+            metadata: Vec::new(),
+            payload: Bytes::from_static(b"key"),
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_calls_share_one_request() {
+        let coalescer = Coalescer::default();
+        let sends = &AtomicU32::new(0);
+        let (release_tx, release_rx) = watch::channel(false);
+        let release_rx = &release_rx;
+        let send = move || async move {
+            sends.fetch_add(1, Ordering::Relaxed);
+            let _ = release_rx.clone().wait_for(|released| *released).await;
+            Ok(Bytes::from_static(b"value"))
+        };
+
+        let (first, second, _) = join3(
+            coalescer.run(key(), (None, None), send),
+            coalescer.run(key(), (None, None), send),
+            async { release_tx.send_replace(true) },
+        )
+        .await;
+        assert_eq!(first.unwrap(), second.unwrap());
+        assert_eq!(sends.load(Ordering::Relaxed), 1);
+        assert!(coalescer.flights.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn waiters_share_the_error() {
+        let coalescer = Coalescer::default();
+        let (release_tx, release_rx) = watch::channel(false);
+        let release_rx = &release_rx;
+        let send = move || async move {
+            let _ = release_rx.clone().wait_for(|released| *released).await;
+            Err(crate::error::actr_error(ErrorCode::Unavailable, "down"))
+        };
+
+        let (first, second, _) = join3(
+            coalescer.run(key(), (None, None), send),
+            coalescer.run(key(), (None, None), send),
+            async { release_tx.send_replace(true) },
+        )
+        .await;
+        assert!(crate::error::has_code(
+            &first.unwrap_err(),
+            ErrorCode::Unavailable
+        ));
+        assert!(crate::error::has_code(
+            &second.unwrap_err(),
+            ErrorCode::Unavailable
+        ));
+    }
+
+    #[tokio::test]
+    async fn waiter_takes_over_when_the_sender_is_aborted() {
+        let coalescer = Coalescer::default();
+        let sends = &AtomicU32::new(0);
+        let (release_tx, release_rx) = watch::channel(false);
+        let release_rx = &release_rx;
+        let send = move || async move {
+            if sends.fetch_add(1, Ordering::Relaxed) > 0 {
+                return Ok(Bytes::from_static(b"value"));
+            }
+            let _ = release_rx.clone().wait_for(|released| *released).await;
+            Err(crate::error::actr_error(ErrorCode::Aborted, "cancelled"))
+        };
+
+        let (first, second, _) = join3(
+            coalescer.run(key(), (None, None), send),
+            coalescer.run(key(), (None, None), send),
+            async { release_tx.send_replace(true) },
+        )
+        .await;
+        assert!(crate::error::has_code(
+            &first.unwrap_err(),
+            ErrorCode::Aborted
+        ));
+        assert_eq!(second.unwrap(), Bytes::from_static(b"value"));
+        assert_eq!(sends.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn waiter_takes_over_when_the_sender_is_dropped() {
+        let coalescer = Coalescer::default();
+        let sends = &AtomicU32::new(0);
+        let send = move || async move {
+            if sends.fetch_add(1, Ordering::Relaxed) == 0 {
+                std::future::pending::<()>().await;
+            }
+            Ok(Bytes::from_static(b"value"))
+        };
+
+        let (first, second) = join(
+            tokio::time::timeout(
+                Duration::from_millis(10),
+                coalescer.run(key(), (None, None), send),
+            ),
+            coalescer.run(key(), (None, None), send),
+        )
+        .await;
+        assert!(first.is_err());
+        assert_eq!(second.unwrap(), Bytes::from_static(b"value"));
+        assert_eq!(sends.load(Ordering::Relaxed), 2);
+        assert!(coalescer.flights.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_leaves_the_request_running() {
+        let coalescer = Coalescer::default();
+        let (release_tx, release_rx) = watch::channel(false);
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let release_rx = &release_rx;
+        let send = move || async move {
+            let _ = release_rx.clone().wait_for(|released| *released).await;
+            Ok(Bytes::from_static(b"value"))
+        };
+
+        let (first, second, _) = join3(
+            coalescer.run(key(), (None, None), send),
+            coalescer.run(key(), (Some(cancel_rx), None), send),
+            async {
+                cancel_tx.send_replace(true);
+                tokio::task::yield_now().await;
+                release_tx.send_replace(true);
+            },
+        )
+        .await;
+        assert_eq!(first.unwrap(), Bytes::from_static(b"value"));
+        assert!(crate::error::has_code(
+            &second.unwrap_err(),
+            ErrorCode::Aborted
+        ));
+    }
+}
@@ -3,18 +3,61 @@ use actr_runtime::context::RuntimeContext;
 use napi::bindgen_prelude::*;
 use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi_derive::napi;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
-use crate::types::{ActrId, ActrType, DataStream, PayloadType, StreamSignal};
+use bytes::Bytes;
+
+use crate::cache::CacheKey;
+use crate::cancel::RequestScope;
+use crate::coalesce::CallKey;
+use crate::error::ErrorCode;
+use crate::lease::{Lifetime, StreamCloseReason, StreamClosed, StreamRegistrationOptions};
+use crate::retry::Retry;
+use crate::routing::{Router, Selection};
+use crate::stream::{
+    Control, StreamReceiver, StreamReceiverOptions, StreamWriter, StreamWriterOptions,
+};
+use crate::types::{
+    ActrId, ActrType, CallOptions, DataStream, DiscoverOptions, LoadBalanceStrategy, PayloadType,
+    StreamSignal,
+};
 
 #[napi]
 pub struct ContextBridge {
     inner: RuntimeContext,
+    router: Arc<Router>,
+    /// Set while handling an incoming request.
+    request: Option<Arc<RequestScope>>,
+}
+
+/// An outbound call with its options resolved, reusable across targets.
+#[derive(Clone)]
+struct OutboundCall {
+    route_key: String,
+    payload_type: actr_protocol::PayloadType,
+    payload: Bytes,
+    metadata: Vec<(String, String)>,
+    timeout_ms: i64,
+    cancel: Option<watch::Receiver<bool>>,
+    /// Whether the caller supplied a cancel token; only then does the call carry a call ID the
+    /// target is told about on cancellation.
+    cancellable: bool,
+    load_balance: Option<LoadBalanceStrategy>,
+    hash_key: Option<String>,
+    tags: Option<crate::tags::Tags>,
+    retry: Retry,
+    coalesce: bool,
+    /// How long the response is cached; `None` when the route is not cached.
+    cache_ttl: Option<Duration>,
 }
 
 impl ContextBridge {
     pub fn try_from_context<C: actr_framework::Context + 'static>(
         ctx: &C,
+        router: Arc<Router>,
     ) -> actr_protocol::ActorResult<Self> {
         use std::any::TypeId;
 
@@ -32,13 +75,412 @@ impl ContextBridge {
 
         Ok(Self {
             inner: runtime_ctx.clone(),
+            router,
+            request: None,
+        })
+    }
+
+    /// Bridge over the node-level context, for calls made outside a handler.
+    pub(crate) fn from_runtime(inner: RuntimeContext, router: Arc<Router>) -> Self {
+        Self {
+            inner,
+            router,
+            request: None,
+        }
+    }
+
+    /// Attach the cancellation and deadline state of the request being handled.
+    pub(crate) fn with_request(mut self, request: Arc<RequestScope>) -> Self {
+        self.request = Some(request);
+        self
+    }
+
+    /// Cap `timeout_ms` to the time left on the request being handled, if any.
+    fn clamp_timeout(&self, timeout_ms: i64) -> Result<i64> {
+        match self.request.as_ref().and_then(|r| r.remaining()) {
+            Some(remaining) if remaining.is_zero() => Err(crate::error::actr_error(
+                ErrorCode::Timeout,
+                "request deadline exceeded",
+            )),
+            Some(remaining) => Ok(timeout_ms.min(remaining.as_millis().max(1) as i64)),
+            None => Ok(timeout_ms),
+        }
+    }
+
+    /// Cancellation receiver of the request being handled, if any.
+    fn parent_cancel(&self) -> Option<watch::Receiver<bool>> {
+        self.request.as_ref().map(|r| r.cancel_rx())
+    }
+
+    pub(crate) fn runtime(&self) -> &RuntimeContext {
+        &self.inner
+    }
+
+    /// Resolve per-call options into an outbound call.
+    fn prepare_call(
+        &self,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        timeout_ms: i64,
+        options: Option<CallOptions>,
+    ) -> Result<OutboundCall> {
+        let options = options.unwrap_or_default();
+        let metadata = options.metadata_entries()?;
+        let cancel = crate::cancel::lookup(options.cancel_token_id)?;
+        let timeout_ms = self.clamp_timeout(options.timeout_ms.unwrap_or(timeout_ms))?;
+        let retry = self
+            .router
+            .retry_policy(&route_key, options.retry.as_ref())?;
+        let coalesce = self
+            .router
+            .coalescer()
+            .applies(&route_key, options.coalesce);
+        let cache_ttl = self.router.response_cache().ttl(&route_key, options.cache);
+
+        Ok(OutboundCall {
+            route_key,
+            payload_type: options.payload_type.unwrap_or(payload_type).into(),
+            payload: Bytes::from(payload.to_vec()),
+            metadata,
+            timeout_ms,
+            cancellable: cancel.is_some(),
+            cancel,
+            load_balance: options.load_balance,
+            hash_key: options.hash_key,
+            tags: options.tags,
+            retry,
+            coalesce,
+            cache_ttl,
+        })
+    }
+
+    /// Pick a target of `target_type` from the router's cached candidates.
+    async fn resolve_target(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        call: &OutboundCall,
+    ) -> Result<actr_protocol::ActrId> {
+        self.router
+            .select(
+                Some(&self.inner),
+                target_type,
+                &Selection {
+                    strategy: call.load_balance,
+                    hash_key: call.hash_key.as_deref(),
+                    tags: call.tags.as_ref(),
+                    route_key: Some(&call.route_key),
+                },
+            )
+            .await
+    }
+
+    /// Cancellation receivers that end a retry pause early.
+    fn retry_cancel(
+        &self,
+        call: &OutboundCall,
+    ) -> (Option<watch::Receiver<bool>>, Option<watch::Receiver<bool>>) {
+        (call.cancel.clone(), self.parent_cancel())
+    }
+
+    /// Retry policy for a call by type: a target that turns out to be gone is always worth one
+    /// more attempt on a freshly discovered candidate.
+    ///
+    /// The second attempt is granted even when no policy is configured, since a call by type
+    /// promises a live instance rather than a particular one: the stale target was evicted from
+    /// the discovery cache, so the retry re-discovers instead of failing on an address that
+    /// simply went away. Errors other than those three still get a single attempt.
+    fn type_retry(call: &OutboundCall) -> Retry {
+        call.retry
+            .clone()
+            .also_on(&[
+                ErrorCode::NotFound,
+                ErrorCode::Unavailable,
+                ErrorCode::Transport,
+            ])
+            .at_least(2)
+    }
+
+    /// Send `call` to a target of `target_type`, resolving the target again on every attempt.
+    async fn call_type(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        call: &OutboundCall,
+    ) -> Result<Bytes> {
+        let retry = Self::type_retry(call);
+        crate::retry::run(&retry, self.retry_cancel(call), || async move {
+            let target = self.resolve_target(target_type, call).await?;
+            let result = self.call_hedged(call, target).await;
+            if let Err(e) = &result
+                && crate::routing::is_stale_target_error(e)
+            {
+                self.router.invalidate(target_type);
+            }
+            result
+        })
+        .await
+    }
+
+    /// Send `call` to a target of `target_type` as a one-way message.
+    async fn tell_type(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        call: &OutboundCall,
+    ) -> Result<()> {
+        let retry = Self::type_retry(call);
+        crate::retry::run(&retry, self.retry_cancel(call), || async move {
+            let target = self.resolve_target(target_type, call).await?;
+            let result = self.tell_target(call, target).await;
+            if let Err(e) = &result
+                && crate::routing::is_stale_target_error(e)
+            {
+                self.router.invalidate(target_type);
+            }
+            result
         })
+        .await
+    }
+
+    /// Send `call` to `target_id` and wait for the response.
+    async fn call_target(
+        &self,
+        call: &OutboundCall,
+        target_id: actr_protocol::ActrId,
+    ) -> Result<Bytes> {
+        let parent_cancel = self.parent_cancel();
+        // Retries may start late; keep every attempt within the request deadline.
+        let timeout_ms = self.clamp_timeout(call.timeout_ms)?;
+        let probe_started = Instant::now();
+        let framed = (call.cancellable || !call.metadata.is_empty())
+            && self.frames_metadata(&target_id, call, timeout_ms).await?;
+        // Probing the target counts against the call's timeout.
+        let timeout_ms = timeout_ms - probe_started.elapsed().as_millis() as i64;
+        if timeout_ms <= 0 {
+            return Err(crate::error::actr_error(
+                ErrorCode::Timeout,
+                "call timed out before it was sent",
+            ));
+        }
+        let mut metadata = call.metadata.clone();
+        let call_id = (call.cancellable && framed).then(|| {
+            let call_id = crate::cancel::next_call_id();
+            metadata.push((crate::cancel::CALL_ID_KEY.to_string(), call_id.clone()));
+            call_id
+        });
+        let payload = crate::headers::encode(&metadata, call.payload.clone());
+        let permit = self.router.admit(&target_id, &call.route_key)?;
+        let in_flight = self.router.track(&target_id);
+        let dest = actr_framework::Dest::Actor(target_id);
+
+        let pending = crate::cancel::with_cancel(parent_cancel.clone(), async {
+            self.inner
+                .call_raw(
+                    &dest,
+                    call.route_key.clone(),
+                    call.payload_type,
+                    payload,
+                    timeout_ms,
+                )
+                .await
+                .map_err(crate::error::protocol_error_to_napi)
+        });
+        let result = crate::cancel::with_cancel(call.cancel.clone(), pending).await;
+
+        let aborted = [&call.cancel, &parent_cancel]
+            .into_iter()
+            .flatten()
+            .any(|rx| *rx.borrow());
+        if let Some(call_id) = call_id
+            && result.is_err()
+            && aborted
+        {
+            self.notify_cancel(dest, call_id);
+        }
+
+        if result.is_ok() {
+            in_flight.succeeded(&call.route_key);
+        }
+        if let Some(permit) = permit {
+            permit.record(&result);
+        }
+        result
+    }
+
+    /// Send `call` to `primary`, hedging it with other candidates of the same type when the
+    /// route is marked idempotent.
+    async fn call_hedged(
+        &self,
+        call: &OutboundCall,
+        primary: actr_protocol::ActrId,
+    ) -> Result<Bytes> {
+        let Some(hedge) = self.router.hedge_plan(&call.route_key) else {
+            return self.call_target(call, primary).await;
+        };
+        let mut targets = self
+            .router
+            .hedge_targets(
+                Some(&self.inner),
+                &primary,
+                &call.route_key,
+                hedge.max_hedges(),
+            )
+            .await;
+        targets.insert(0, primary);
+
+        crate::hedge::run(&hedge, targets, call.cancel.clone(), |target, cancel| {
+            let call = OutboundCall {
+                cancel: Some(cancel),
+                ..call.clone()
+            };
+            async move { self.call_target(&call, target).await }
+        })
+        .await
+    }
+
+    /// Answer `call` from the node's response cache when its route is cached, otherwise fetch
+    /// it as `coalesced` does.
+    async fn cached<Fut>(
+        &self,
+        dest: String,
+        call: &OutboundCall,
+        send: impl FnOnce() -> Fut,
+    ) -> Result<Bytes>
+    where
+        Fut: Future<Output = Result<Bytes>>,
+    {
+        let Some(ttl) = call.cache_ttl else {
+            return self.coalesced(dest, call, send).await;
+        };
+        let key = CacheKey::new(
+            dest.clone(),
+            &call.route_key,
+            call.payload_type as i32,
+            &call.metadata,
+            &call.payload,
+        );
+        self.router
+            .response_cache()
+            .get_or_fetch(key, ttl, || self.coalesced(dest, call, send))
+            .await
+    }
+
+    /// Run `send`, or share the response of an identical call to `dest` already in flight when
+    /// `call` opted into coalescing.
+    async fn coalesced<Fut>(
+        &self,
+        dest: String,
+        call: &OutboundCall,
+        send: impl FnOnce() -> Fut,
+    ) -> Result<Bytes>
+    where
+        Fut: Future<Output = Result<Bytes>>,
+    {
+        if !call.coalesce {
+            return send().await;
+        }
+        let key = CallKey {
+            dest,
+            route_key: call.route_key.clone(),
+            payload_type: call.payload_type as i32,
+            metadata: call.metadata.clone(),
+            payload: call.payload.clone(),
+        };
+        self.router
+            .coalescer()
+            .run(key, self.retry_cancel(call), send)
+            .await
+    }
+
+    /// Send `call` to `target_id` as a one-way message.
+    async fn tell_target(
+        &self,
+        call: &OutboundCall,
+        target_id: actr_protocol::ActrId,
+    ) -> Result<()> {
+        if !call.metadata.is_empty() {
+            self.frames_metadata(&target_id, call, self.clamp_timeout(call.timeout_ms)?)
+                .await?;
+        }
+        let payload = crate::headers::encode(&call.metadata, call.payload.clone());
+        let permit = self.router.admit(&target_id, &call.route_key)?;
+
+        let tell = crate::cancel::with_cancel(self.parent_cancel(), async {
+            self.inner
+                .tell_raw(
+                    &actr_framework::Dest::Actor(target_id),
+                    call.route_key.clone(),
+                    call.payload_type,
+                    payload,
+                )
+                .await
+                .map_err(crate::error::protocol_error_to_napi)
+        });
+        let result = crate::cancel::with_cancel(call.cancel.clone(), tell).await;
+        if let Some(permit) = permit {
+            permit.record(&result);
+        }
+        result
+    }
+
+    /// Whether request metadata can be framed into payloads sent to `target`. Only actr-ts nodes
+    /// strip the frame, so metadata for a target without the `actr.Tags` route is rejected.
+    ///
+    /// Calls without metadata never probe the target and are framed only for targets already
+    /// known to be actr-ts nodes. The probe is bounded by `timeout_ms` and the call's
+    /// cancellation, and fails with its own error when the target could not be asked.
+    async fn frames_metadata(
+        &self,
+        target: &actr_protocol::ActrId,
+        call: &OutboundCall,
+        timeout_ms: i64,
+    ) -> Result<bool> {
+        if call.metadata.is_empty() {
+            return Ok(self.router.known_actr_ts(target));
+        }
+        let probe = crate::cancel::with_cancel(
+            self.parent_cancel(),
+            self.router
+                .is_actr_ts(Some(&self.inner), target, timeout_ms),
+        );
+        if crate::cancel::with_cancel(call.cancel.clone(), probe).await? {
+            return Ok(true);
+        }
+        Err(crate::error::actr_error(
+            ErrorCode::InvalidArgument,
+            format!(
+                "{} is not an actr-ts node and cannot receive call metadata",
+                crate::identity::id_to_string(target)
+            ),
+        ))
+    }
+
+    /// Tell the target that call `call_id` was cancelled. Best effort; failures are ignored.
+    fn notify_cancel(&self, dest: actr_framework::Dest, call_id: String) {
+        let ctx = self.inner.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ctx
+                .tell_raw(
+                    &dest,
+                    crate::cancel::CANCEL_ROUTE_KEY.to_string(),
+                    actr_protocol::PayloadType::RpcSignal,
+                    Bytes::from(call_id),
+                )
+                .await
+            {
+                tracing::debug!("failed to send cancellation notice: {e}");
+            }
+        });
     }
 }
 
 #[napi]
 impl ContextBridge {
     /// Call remote actor.
+    ///
+    /// With `options.cancelTokenId`, cancelling the token drops the pending call, rejects with
+    /// `ABORTED`, and notifies the target when it is known to be an actr-ts node, so its handler
+    /// can stop work. Inside a handler, the timeout is capped to the request's remaining deadline
+    /// and the call is cancelled along with the request.
     #[napi]
     pub async fn call_raw(
         &self,
@@ -47,21 +489,18 @@ impl ContextBridge {
         payload_type: PayloadType,
         payload: Buffer,
         timeout_ms: i64,
+        options: Option<CallOptions>,
     ) -> Result<Buffer> {
-        let target_id: actr_protocol::ActrId = target.into();
-        let proto_payload_type: actr_protocol::PayloadType = payload_type.into();
-
+        let target_id: actr_protocol::ActrId = target.try_into()?;
+        let call = self.prepare_call(route_key, payload_type, payload, timeout_ms, options)?;
+        let dest = crate::identity::id_to_string(&target_id);
         let response = self
-            .inner
-            .call_raw(
-                &actr_framework::Dest::Actor(target_id),
-                route_key,
-                proto_payload_type,
-                bytes::Bytes::from(payload.to_vec()),
-                timeout_ms,
-            )
-            .await
-            .map_err(crate::error::protocol_error_to_napi)?;
+            .cached(dest, &call, || {
+                crate::retry::run(&call.retry, self.retry_cancel(&call), || {
+                    self.call_hedged(&call, target_id.clone())
+                })
+            })
+            .await?;
 
         Ok(response.to_vec().into())
     }
@@ -74,38 +513,148 @@ impl ContextBridge {
         route_key: String,
         payload_type: PayloadType,
         payload: Buffer,
+        options: Option<CallOptions>,
     ) -> Result<()> {
-        let target_id: actr_protocol::ActrId = target.into();
-        let proto_payload_type: actr_protocol::PayloadType = payload_type.into();
+        let target_id: actr_protocol::ActrId = target.try_into()?;
+        let call = self.prepare_call(route_key, payload_type, payload, 0, options)?;
+        crate::retry::run(&call.retry, self.retry_cancel(&call), || {
+            self.tell_target(&call, target_id.clone())
+        })
+        .await
+    }
 
-        self.inner
-            .tell_raw(
-                &actr_framework::Dest::Actor(target_id),
-                route_key,
-                proto_payload_type,
-                bytes::Bytes::from(payload.to_vec()),
-            )
-            .await
-            .map_err(crate::error::protocol_error_to_napi)?;
+    /// Call an actor of the given type.
+    ///
+    /// Candidates are discovered once and cached per type, and one is picked per call using
+    /// `options.loadBalance`. If the chosen target turns out to be gone, the cache is dropped and
+    /// the call is retried once on a freshly discovered target.
+    #[napi]
+    pub async fn call_raw_by_type(
+        &self,
+        target_type: ActrType,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        timeout_ms: i64,
+        options: Option<CallOptions>,
+    ) -> Result<Buffer> {
+        let call = self.prepare_call(route_key, payload_type, payload, timeout_ms, options)?;
+        let target_type: actr_protocol::ActrType = target_type.into();
+        let dest = crate::identity::type_to_string(&target_type);
+        let response = self
+            .cached(dest, &call, || self.call_type(&target_type, &call))
+            .await?;
 
-        Ok(())
+        Ok(response.to_vec().into())
+    }
+
+    /// Send one-way message to an actor of the given type, using the cached candidates.
+    #[napi]
+    pub async fn tell_raw_by_type(
+        &self,
+        target_type: ActrType,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let call = self.prepare_call(route_key, payload_type, payload, 0, options)?;
+        self.tell_type(&target_type.into(), &call).await
+    }
+
+    /// Call the actor of the given type that owns `shard_key`.
+    ///
+    /// Targets are placed on a consistent-hash ring with virtual nodes, so the same key keeps
+    /// reaching the same actor and only a share of keys move when actors join or leave.
+    #[napi]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn call_raw_by_key(
+        &self,
+        target_type: ActrType,
+        shard_key: String,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        timeout_ms: i64,
+        options: Option<CallOptions>,
+    ) -> Result<Buffer> {
+        let mut call = self.prepare_call(route_key, payload_type, payload, timeout_ms, options)?;
+        call.load_balance = Some(LoadBalanceStrategy::ConsistentHash);
+        let target_type: actr_protocol::ActrType = target_type.into();
+        let dest = format!(
+            "{}#{shard_key}",
+            crate::identity::type_to_string(&target_type)
+        );
+        call.hash_key = Some(shard_key);
+        let response = self
+            .cached(dest, &call, || self.call_type(&target_type, &call))
+            .await?;
+
+        Ok(response.to_vec().into())
+    }
+
+    /// Send one-way message to the actor of the given type that owns `shard_key`.
+    #[napi]
+    pub async fn tell_raw_by_key(
+        &self,
+        target_type: ActrType,
+        shard_key: String,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let mut call = self.prepare_call(route_key, payload_type, payload, 0, options)?;
+        call.load_balance = Some(LoadBalanceStrategy::ConsistentHash);
+        call.hash_key = Some(shard_key);
+        self.tell_type(&target_type.into(), &call).await
     }
 
     /// Discover an actor of the given type.
+    ///
+    /// With `options`, the actor is picked from the node's cached candidates using the given
+    /// load-balancing strategy; without, the runtime picks one. `options.waitMs` keeps
+    /// re-discovering until `options.minCandidates` are available.
     #[napi]
-    pub async fn discover(&self, target_type: ActrType) -> Result<ActrId> {
+    pub async fn discover(
+        &self,
+        target_type: ActrType,
+        options: Option<DiscoverOptions>,
+    ) -> Result<ActrId> {
         let proto_type: actr_protocol::ActrType = target_type.into();
+        if let Some(options) = options {
+            if options.waits() {
+                let wait = self.clamp_timeout(options.wait_ms.unwrap_or(0) as i64)?;
+                let min = options.min_candidates.unwrap_or(1);
+                self.router
+                    .wait_for(
+                        Some(&self.inner),
+                        &proto_type,
+                        self.router.discovery_count(),
+                        min,
+                        Duration::from_millis(wait as u64),
+                        options.tags.as_ref(),
+                    )
+                    .await?;
+            }
+            let id = self
+                .router
+                .select(Some(&self.inner), &proto_type, &options.selection())
+                .await?;
+            return Ok(self.router.id_to_js(id));
+        }
+
         let id = Context::discover_route_candidate(&self.inner, &proto_type)
             .await
             .map_err(crate::error::protocol_error_to_napi)?;
 
-        Ok(id.into())
+        Ok(self.router.id_to_js(id))
     }
 
     /// Send DataStream chunk to target.
     #[napi]
     pub async fn send_data_stream(&self, target: ActrId, chunk: DataStream) -> Result<()> {
-        let target_id: actr_protocol::ActrId = target.into();
+        let target_id: actr_protocol::ActrId = target.try_into()?;
         let chunk: actr_protocol::DataStream = chunk.into();
 
         Context::send_data_stream(&self.inner, &actr_framework::Dest::Actor(target_id), chunk)
@@ -116,50 +665,173 @@ impl ContextBridge {
     }
 
     /// Register a callback for an incoming DataStream.
+    ///
+    /// A sender's `end()` arrives as a signal with `end: true`; its `abort(reason)` as an error
+    /// with code `STREAM_ABORTED`. The registration is removed once the stream ends, aborts, idles
+    /// past `idleTimeoutMs`, loses its sender or outlives the workload; `onClose` then receives
+    /// the reason.
     #[napi]
     pub async fn register_stream(
         &self,
         stream_id: String,
         #[napi(ts_arg_type = "(err: Error | null, signal: StreamSignal) => void")]
         callback: ThreadsafeFunction<StreamSignal>,
+        options: Option<StreamRegistrationOptions>,
+        #[napi(
+            ts_arg_type = "((err: Error | null, event: StreamClosed) => void) | undefined | null"
+        )]
+        on_close: Option<ThreadsafeFunction<StreamClosed>>,
     ) -> Result<()> {
         use actr_framework::Context;
+        let options = options.unwrap_or_default();
+        let lease = self.router.streams().lease(&stream_id);
         let callback = Arc::new(callback);
+        let handler_lease = lease.clone();
+        let serial_numbers = self.router.serial_numbers();
         self.inner
-            .register_stream(stream_id, move |chunk, sender| {
+            .register_stream(stream_id.clone(), move |chunk, sender| {
                 let callback = callback.clone();
+                let lease = handler_lease.clone();
                 Box::pin(async move {
-                    let chunk_bridge: DataStream = chunk.into();
-                    let sender_bridge: ActrId = sender.into();
-                    callback.call(
-                        Ok(StreamSignal {
-                            chunk: chunk_bridge,
-                            sender: sender_bridge,
+                    if lease.is_closed() {
+                        return Ok(());
+                    }
+                    lease.touch(&sender);
+                    let control = Control::of(&chunk);
+                    let closing = match &control {
+                        Some(Control::End) => Some(StreamCloseReason::Ended),
+                        Some(Control::Abort(_)) => Some(StreamCloseReason::Aborted),
+                        None => None,
+                    };
+                    let signal = match control {
+                        Some(Control::Abort(reason)) => {
+                            Err(Control::aborted(&chunk.stream_id, &reason))
+                        }
+                        control => Ok(StreamSignal {
+                            chunk: chunk.into(),
+                            sender: serial_numbers.id_to_js(sender),
+                            end: matches!(control, Some(Control::End)).then_some(true),
+                            missed: None,
                         }),
-                        ThreadsafeFunctionCallMode::NonBlocking,
-                    );
+                    };
+                    callback.call(signal, ThreadsafeFunctionCallMode::NonBlocking);
+                    if let Some(reason) = closing {
+                        lease.close(reason);
+                    }
                     Ok(())
                 })
             })
             .await
             .map_err(crate::error::protocol_error_to_napi)?;
+
+        crate::lease::supervise(
+            self.router.clone(),
+            self.inner.clone(),
+            lease,
+            Lifetime::new(options.idle_timeout_ms, options.peer_check_ms),
+            move |reason, sender| {
+                if let Some(on_close) = on_close {
+                    let event = StreamClosed {
+                        stream_id,
+                        reason,
+                        sender: sender.map(|id| serial_numbers.id_to_js(id)),
+                    };
+                    on_close.call(Ok(event), ThreadsafeFunctionCallMode::NonBlocking);
+                }
+            },
+        );
         Ok(())
     }
 
-    /// Unregister a DataStream callback.
+    /// Open a stream to `target` whose writer numbers and timestamps chunks.
+    #[napi]
+    pub fn open_stream(
+        &self,
+        target: ActrId,
+        stream_id: String,
+        options: Option<StreamWriterOptions>,
+    ) -> Result<StreamWriter> {
+        let target: actr_protocol::ActrId = target.try_into()?;
+        StreamWriter::new(
+            &self.inner,
+            target,
+            self.router.serial_numbers(),
+            stream_id,
+            options.unwrap_or_default(),
+        )
+    }
+
+    /// Register a buffered receiver for an incoming DataStream, read with `next()`.
+    #[napi]
+    pub async fn receive_stream(
+        &self,
+        stream_id: String,
+        options: Option<StreamReceiverOptions>,
+    ) -> Result<StreamReceiver> {
+        StreamReceiver::register(
+            self.router.clone(),
+            &self.inner,
+            stream_id,
+            options.unwrap_or_default(),
+        )
+        .await
+    }
+
+    /// Unregister a DataStream callback; its `onClose` reports `Unregistered`.
     #[napi]
     pub async fn unregister_stream(&self, stream_id: String) -> Result<()> {
         use actr_framework::Context;
-        self.inner
+        let lease = self.router.streams().take(&stream_id);
+        let result = self
+            .inner
             .unregister_stream(&stream_id)
             .await
-            .map_err(crate::error::protocol_error_to_napi)?;
-        Ok(())
+            .map_err(crate::error::protocol_error_to_napi);
+        if let Some(lease) = lease {
+            lease.close(StreamCloseReason::Unregistered);
+        }
+        result
+    }
+
+    /// Absolute deadline of the request being handled, in ms since Unix epoch.
+    #[napi(getter)]
+    pub fn deadline_ms(&self) -> Option<f64> {
+        self.request.as_ref().and_then(|r| r.deadline_ms())
+    }
+
+    /// Time left before the request deadline, in ms.
+    #[napi]
+    pub fn remaining_ms(&self) -> Option<f64> {
+        self.request
+            .as_ref()
+            .and_then(|r| r.remaining())
+            .map(|remaining| remaining.as_millis() as f64)
+    }
+
+    /// Resolve `true` when the request being handled is cancelled by its caller or its
+    /// deadline passes, or `false` once the handler finishes (or outside a request).
+    #[napi]
+    pub async fn wait_cancelled(&self) -> bool {
+        match &self.request {
+            Some(request) => request.wait().await,
+            None => false,
+        }
+    }
+
+    /// Get the ID of the actor that sent the current request.
+    #[napi]
+    pub fn caller_id(&self) -> Option<ActrId> {
+        self.inner
+            .caller_id()
+            .cloned()
+            .map(|id| self.router.id_to_js(id))
     }
 
     /// Get the current RPC call ID from the runtime context.
+    ///
+    /// Deprecated: returns the caller's ActrId; use `callerId()` or `envelope.caller`.
     #[napi]
     pub fn call_id(&self) -> Option<ActrId> {
-        self.inner.caller_id().cloned().map(Into::into)
+        self.caller_id()
     }
 }
@@ -1,15 +1,194 @@
 // Error conversion utilities
 // Note: We can't implement From traits for foreign types, so we use helper functions
+//
+// Every error that crosses into JS carries a stable code in its reason, formatted as
+// `[ACTR_<CODE>] <message>`, followed by one `caused by: <source>` line per source in the
+// chain. The TypeScript layer parses this back into an `ActrError`. Every `ProtocolError` and
+// `RuntimeError` variant maps to a code of its own, so callers never have to match on messages.
+//
+// Application errors thrown by a remote handler travel as a `BusinessError` whose message starts
+// with `[ACTR_APP:<code>] <message>` (plus an optional `details: <hex>` line) and are passed
+// through to the caller verbatim so the original code survives the round trip. The marker is only
+// recognised at the start, so other errors that merely mention it keep their own code.
+
+use std::fmt;
+
+/// Marker prefixing an application error raised by a JS `dispatch` handler.
+const APPLICATION_ERROR_MARKER: &str = "[ACTR_APP:";
+
+/// Stable error codes surfaced to JS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Timeout,
+    Aborted,
+    NotFound,
+    NoCandidates,
+    CircuitOpen,
+    StreamOverflow,
+    StreamAborted,
+    Unavailable,
+    UnknownRoute,
+    /// The target's ACL rejected the caller.
+    PermissionDenied,
+    Serialization,
+    Deserialization,
+    Transport,
+    /// Raised by the bindings for input they reject before it reaches the runtime.
+    InvalidArgument,
+    InvalidState,
+    Application,
+    Config,
+    Runtime,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 19] = [
+        ErrorCode::Timeout,
+        ErrorCode::Aborted,
+        ErrorCode::NotFound,
+        ErrorCode::NoCandidates,
+        ErrorCode::CircuitOpen,
+        ErrorCode::StreamOverflow,
+        ErrorCode::StreamAborted,
+        ErrorCode::Unavailable,
+        ErrorCode::UnknownRoute,
+        ErrorCode::PermissionDenied,
+        ErrorCode::Serialization,
+        ErrorCode::Deserialization,
+        ErrorCode::Transport,
+        ErrorCode::InvalidArgument,
+        ErrorCode::InvalidState,
+        ErrorCode::Application,
+        ErrorCode::Config,
+        ErrorCode::Runtime,
+        ErrorCode::Internal,
+    ];
+
+    /// Code named `code`, e.g. `TIMEOUT`, ignoring case.
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(code))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::Aborted => "ABORTED",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::NoCandidates => "NO_CANDIDATES",
+            ErrorCode::CircuitOpen => "CIRCUIT_OPEN",
+            ErrorCode::StreamOverflow => "STREAM_OVERFLOW",
+            ErrorCode::StreamAborted => "STREAM_ABORTED",
+            ErrorCode::Unavailable => "UNAVAILABLE",
+            ErrorCode::UnknownRoute => "UNKNOWN_ROUTE",
+            ErrorCode::PermissionDenied => "PERMISSION_DENIED",
+            ErrorCode::Serialization => "SERIALIZATION",
+            ErrorCode::Deserialization => "DESERIALIZATION",
+            ErrorCode::Transport => "TRANSPORT",
+            ErrorCode::InvalidArgument => "INVALID_ARGUMENT",
+            ErrorCode::InvalidState => "INVALID_STATE",
+            ErrorCode::Application => "APPLICATION",
+            ErrorCode::Config => "CONFIG",
+            ErrorCode::Runtime => "RUNTIME",
+            ErrorCode::Internal => "INTERNAL",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Build a coded napi error from a message.
+pub fn actr_error(code: ErrorCode, message: impl fmt::Display) -> napi::Error {
+    napi::Error::from_reason(format!("[ACTR_{code}] {message}"))
+}
+
+/// Code `e` was built with, e.g. `TIMEOUT`.
+pub fn code_of(e: &napi::Error) -> Option<&str> {
+    e.reason
+        .strip_prefix("[ACTR_")?
+        .split_once(']')
+        .map(|(code, _)| code)
+}
+
+/// Whether `e` was built with `code`.
+pub fn has_code(e: &napi::Error, code: ErrorCode) -> bool {
+    code_of(e) == Some(code.as_str())
+}
+
+/// Build a coded napi error from an error value, keeping its source chain.
+fn actr_error_with_chain(code: ErrorCode, e: &dyn std::error::Error) -> napi::Error {
+    let mut reason = format!("[ACTR_{code}] {e}");
+    let mut source = e.source();
+    while let Some(cause) = source {
+        reason.push_str(&format!("\ncaused by: {cause}"));
+        source = cause.source();
+    }
+    napi::Error::from_reason(reason)
+}
+
+pub fn protocol_error_code(e: &actr_protocol::ProtocolError) -> ErrorCode {
+    use actr_protocol::ProtocolError;
+
+    match e {
+        ProtocolError::Timeout { .. } => ErrorCode::Timeout,
+        ProtocolError::TargetNotFound { .. } => ErrorCode::NotFound,
+        ProtocolError::TargetUnavailable { .. } => ErrorCode::Unavailable,
+        ProtocolError::UnknownRoute { .. } => ErrorCode::UnknownRoute,
+        ProtocolError::PermissionDenied { .. } => ErrorCode::PermissionDenied,
+        ProtocolError::SerializationError { .. } | ProtocolError::EncodeError { .. } => {
+            ErrorCode::Serialization
+        }
+        ProtocolError::DeserializationError { .. } | ProtocolError::DecodeError { .. } => {
+            ErrorCode::Deserialization
+        }
+        ProtocolError::TransportError { .. } => ErrorCode::Transport,
+        ProtocolError::InvalidStateTransition { .. } => ErrorCode::InvalidState,
+        ProtocolError::BusinessError { .. } => ErrorCode::Application,
+    }
+}
 
 pub fn protocol_error_to_napi(e: actr_protocol::ProtocolError) -> napi::Error {
-    napi::Error::from_reason(format!("Protocol error: {}", e))
+    if let actr_protocol::ProtocolError::BusinessError(reason) = &e
+        && reason.starts_with(APPLICATION_ERROR_MARKER)
+    {
+        return napi::Error::from_reason(reason.clone());
+    }
+    actr_error_with_chain(protocol_error_code(&e), &e)
+}
+
+/// Convert a rejection from the JS `dispatch` handler into the error returned to the caller.
+///
+/// The reason is kept intact so an encoded application error reaches the remote side unchanged.
+pub fn dispatch_error_to_protocol(e: napi::Error) -> actr_protocol::ProtocolError {
+    actr_protocol::ProtocolError::BusinessError(e.reason)
+}
+
+pub fn runtime_error_code(e: &actr_runtime::RuntimeError) -> ErrorCode {
+    use actr_runtime::RuntimeError;
+
+    match e {
+        RuntimeError::ProtocolError(e) => protocol_error_code(e),
+        RuntimeError::ConfigurationError { .. } => ErrorCode::Config,
+        RuntimeError::TransportError { .. } => ErrorCode::Transport,
+        RuntimeError::TimeoutError { .. } => ErrorCode::Timeout,
+        RuntimeError::ActorNotFound { .. } => ErrorCode::NotFound,
+        RuntimeError::PermissionDenied { .. } => ErrorCode::PermissionDenied,
+        RuntimeError::ShutdownError { .. } => ErrorCode::Unavailable,
+        RuntimeError::Other { .. } => ErrorCode::Runtime,
+    }
 }
 
 #[allow(dead_code)]
 pub fn runtime_error_to_napi(e: actr_runtime::RuntimeError) -> napi::Error {
-    napi::Error::from_reason(format!("Runtime error: {}", e))
+    actr_error_with_chain(runtime_error_code(&e), &e)
 }
 
 pub fn config_error_to_napi(e: actr_config::ConfigError) -> napi::Error {
-    napi::Error::from_reason(format!("Config error: {}", e))
+    actr_error_with_chain(ErrorCode::Config, &e)
 }
@@ -0,0 +1,55 @@
+import { describe, it, expect } from 'vitest';
+import { ActrError, ApplicationError, ErrorCode, encodeDispatchError } from '../typescript/error';
+
+describe('ActrError', () => {
+  it('should parse coded native errors', () => {
+    const native = new Error('[ACTR_TIMEOUT] Timeout\ncaused by: deadline elapsed');
+    const err = ActrError.from(native);
+    expect(err).toBeInstanceOf(ActrError);
+    expect(err.code).toBe(ErrorCode.Timeout);
+    expect(err.message).toBe('Timeout');
+    expect(err.causes).toEqual(['deadline elapsed']);
+    expect(err.cause).toBe(native);
+  });
+
+  it.each([
+    ['[ACTR_TIMEOUT] Timeout', ErrorCode.Timeout],
+    ['[ACTR_NOT_FOUND] Target not found: acme+Echo', ErrorCode.NotFound],
+    ['[ACTR_UNAVAILABLE] Target unavailable: 1@1/acme+Echo', ErrorCode.Unavailable],
+    ['[ACTR_UNKNOWN_ROUTE] Unknown route: echo.Missing', ErrorCode.UnknownRoute],
+    ['[ACTR_PERMISSION_DENIED] Permission denied: acme+Client', ErrorCode.PermissionDenied],
+    ['[ACTR_SERIALIZATION] Encode error: buffer too small', ErrorCode.Serialization],
+    ['[ACTR_DESERIALIZATION] Decode error: invalid wire type', ErrorCode.Deserialization],
+    ['[ACTR_TRANSPORT] Transport error: connection reset', ErrorCode.Transport],
+    ['[ACTR_INVALID_STATE] Invalid state transition: node not started', ErrorCode.InvalidState],
+    ['[ACTR_CONFIG] Configuration error: missing signaling url', ErrorCode.Config],
+    ['[ACTR_RUNTIME] mailbox closed', ErrorCode.Runtime],
+  ])('should keep the code of %s', (reason, code) => {
+    const err = ActrError.from(new Error(reason));
+    expect(err.code).toBe(code);
+    expect(err.message).toBe(reason.slice(reason.indexOf('] ') + 2));
+  });
+
+  it('should report uncoded errors as INTERNAL', () => {
+    const err = ActrError.from(new Error('boom'));
+    expect(err.code).toBe(ErrorCode.Internal);
+    expect(err.message).toBe('boom');
+  });
+
+  it('should only decode application errors at the start of the message', () => {
+    const err = ActrError.from(new Error('[ACTR_INTERNAL] upstream said [ACTR_APP:X] no'));
+    expect(err).not.toBeInstanceOf(ApplicationError);
+    expect(err.code).toBe(ErrorCode.Internal);
+    expect(ActrError.from(new Error('wrapped: [ACTR_APP:X] no'))).not.toBeInstanceOf(ApplicationError);
+  });
+
+  it('should round-trip application errors thrown by handlers', () => {
+    const thrown = new ApplicationError('OUT_OF_STOCK', 'item 42', Buffer.from([1, 2, 3]));
+    const err = ActrError.from(encodeDispatchError(thrown));
+    expect(err).toBeInstanceOf(ApplicationError);
+    expect(err.code).toBe(ErrorCode.Application);
+    expect((err as ApplicationError).applicationCode).toBe('OUT_OF_STOCK');
+    expect(err.message).toBe('item 42');
+    expect((err as ApplicationError).details).toEqual(Buffer.from([1, 2, 3]));
+  });
+});
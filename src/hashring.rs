@@ -0,0 +1,120 @@
+// Consistent-hash ring over discovered actors
+//
+// Each member is placed on the ring at `virtual_nodes` points derived from its canonical ActrId
+// string, and a key maps to the first point at or after its own hash. Hashes are process- and
+// platform-independent, so every node building a ring from the same members routes a key to the
+// same actor; when a member joins or leaves only the keys of its own arcs move.
+
+/// Default number of ring points per member.
+pub const DEFAULT_VIRTUAL_NODES: u32 = 160;
+
+pub struct HashRing {
+    /// Sorted by hash; the value indexes `members`.
+    points: Vec<(u64, usize)>,
+    members: Vec<actr_protocol::ActrId>,
+}
+
+impl HashRing {
+    pub fn new(members: &[actr_protocol::ActrId], virtual_nodes: u32) -> Self {
+        let virtual_nodes = virtual_nodes.max(1);
+        let mut points = Vec::with_capacity(members.len() * virtual_nodes as usize);
+        for (index, member) in members.iter().enumerate() {
+            let id = crate::identity::id_to_string(member);
+            for vnode in 0..virtual_nodes {
+                points.push((hash([id.as_bytes(), b"#", &vnode.to_be_bytes()]), index));
+            }
+        }
+        // Ties between members are broken by ActrId so the order does not depend on discovery.
+        points.sort_unstable_by(|(a, i), (b, j)| {
+            a.cmp(b).then_with(|| {
+                crate::identity::id_to_string(&members[*i])
+                    .cmp(&crate::identity::id_to_string(&members[*j]))
+            })
+        });
+
+        Self {
+            points,
+            members: members.to_vec(),
+        }
+    }
+
+    /// Member owning `key`, or `None` if the ring is empty.
+    pub fn get(&self, key: &str) -> Option<&actr_protocol::ActrId> {
+        let hash = hash([key.as_bytes()]);
+        let slot = self.points.partition_point(|(point, _)| *point < hash);
+        let (_, index) = self.points.get(slot).or_else(|| self.points.first())?;
+        Some(&self.members[*index])
+    }
+}
+
+/// 64-bit FNV-1a followed by a SplitMix64 finalizer to spread similar inputs around the ring.
+pub(crate) fn hash<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.into_iter().flatten() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn members(count: u64) -> Vec<actr_protocol::ActrId> {
+        (1..=count)
+            .map(|serial| crate::identity::parse_id(&format!("{serial}@1/acme+cache")).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn empty_ring_has_no_owner() {
+        assert!(
+            HashRing::new(&[], DEFAULT_VIRTUAL_NODES)
+                .get("key")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn owner_does_not_depend_on_member_order() {
+        let forward = members(5);
+        let mut reversed = forward.clone();
+        reversed.reverse();
+        let (a, b) = (
+            HashRing::new(&forward, DEFAULT_VIRTUAL_NODES),
+            HashRing::new(&reversed, DEFAULT_VIRTUAL_NODES),
+        );
+        for key in (0..200).map(|i| format!("user:{i}")) {
+            assert_eq!(a.get(&key), b.get(&key));
+        }
+    }
+
+    #[test]
+    fn removing_a_member_only_moves_its_keys() {
+        let all = members(5);
+        let before = HashRing::new(&all, DEFAULT_VIRTUAL_NODES);
+        let after = HashRing::new(&all[1..], DEFAULT_VIRTUAL_NODES);
+        for key in (0..500).map(|i| format!("user:{i}")) {
+            let owner = before.get(&key).unwrap();
+            if *owner != all[0] {
+                assert_eq!(after.get(&key), Some(owner));
+            }
+        }
+    }
+
+    #[test]
+    fn keys_spread_over_all_members() {
+        let all = members(4);
+        let ring = HashRing::new(&all, DEFAULT_VIRTUAL_NODES);
+        let mut counts = vec![0; all.len()];
+        for key in (0..4000).map(|i| format!("user:{i}")) {
+            let owner = ring.get(&key).unwrap();
+            counts[all.iter().position(|m| m == owner).unwrap()] += 1;
+        }
+        assert!(counts.iter().all(|count| *count > 500), "{counts:?}");
+    }
+}
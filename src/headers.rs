@@ -0,0 +1,149 @@
+// Request metadata framing
+//
+// The runtime's `call_raw`/`tell_raw` only carry a route key and an opaque payload, so request
+// metadata set through `CallOptions` is framed in front of the payload and stripped again by
+// `DynamicDispatcher` before the handler sees it. Only actr-ts nodes strip frames, so metadata is
+// only framed for targets known to be actr-ts nodes (they answer `actr.Tags`); calls carrying
+// metadata to any other actor are rejected rather than corrupting their payload.
+//
+// Frames start with a 0x00 byte, which is never a valid first byte of a non-empty protobuf
+// message, so unframed payloads pass through as-is. A payload that starts with the frame magic
+// but does not parse as a frame is rejected instead of being handed to the handler reinterpreted.
+//
+// Layout: MAGIC | u16 entry count | (u16 key len | key | u32 value len | value)* | payload
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::error::{ErrorCode, actr_error};
+
+const MAGIC: [u8; 4] = [0x00, b'A', b'H', 0x01];
+
+/// Reserved metadata key carrying `CallOptions.idempotencyKey`.
+pub const IDEMPOTENCY_KEY: &str = "actr-idempotency-key";
+/// Reserved metadata key carrying `CallOptions.priority`.
+pub const PRIORITY_KEY: &str = "actr-priority";
+
+/// Prefix `payload` with a metadata frame. Returns the payload unchanged if there is no metadata.
+pub fn encode(metadata: &[(String, String)], payload: Bytes) -> Bytes {
+    if metadata.is_empty() {
+        return payload;
+    }
+
+    let mut buf = BytesMut::with_capacity(payload.len() + 64);
+    buf.put_slice(&MAGIC);
+    buf.put_u16(metadata.len() as u16);
+    for (key, value) in metadata {
+        buf.put_u16(key.len() as u16);
+        buf.put_slice(key.as_bytes());
+        buf.put_u32(value.len() as u32);
+        buf.put_slice(value.as_bytes());
+    }
+    buf.put_slice(&payload);
+    buf.freeze()
+}
+
+/// Split a possibly framed payload into its metadata entries and the original payload.
+///
+/// Fails with `DESERIALIZATION` when the payload starts with the frame magic but is not a frame.
+pub fn decode(payload: Bytes) -> napi::Result<(Vec<(String, String)>, Bytes)> {
+    if !payload.starts_with(&MAGIC) {
+        return Ok((Vec::new(), payload));
+    }
+
+    try_decode(payload.slice(MAGIC.len()..)).ok_or_else(|| {
+        actr_error(
+            ErrorCode::Deserialization,
+            "payload starts with a metadata frame marker but is not a valid frame",
+        )
+    })
+}
+
+fn try_decode(mut buf: Bytes) -> Option<(Vec<(String, String)>, Bytes)> {
+    fn take_string(buf: &mut Bytes, len: usize) -> Option<String> {
+        if buf.remaining() < len {
+            return None;
+        }
+        String::from_utf8(buf.split_to(len).to_vec()).ok()
+    }
+
+    if buf.remaining() < 2 {
+        return None;
+    }
+    let count = buf.get_u16() as usize;
+    let mut metadata = Vec::with_capacity(count);
+    for _ in 0..count {
+        if buf.remaining() < 2 {
+            return None;
+        }
+        let key_len = buf.get_u16() as usize;
+        let key = take_string(&mut buf, key_len)?;
+        if buf.remaining() < 4 {
+            return None;
+        }
+        let value_len = buf.get_u32() as usize;
+        let value = take_string(&mut buf, value_len)?;
+        metadata.push((key, value));
+    }
+    Some((metadata, buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_metadata_and_payload() {
+        let metadata = entries(&[(PRIORITY_KEY, "high"), ("trace", ""), ("ключ", "значение")]);
+        let payload = Bytes::from_static(b"\x0a\x03abc");
+        let (decoded, rest) = decode(encode(&metadata, payload.clone())).unwrap();
+        assert_eq!(decoded, metadata);
+        assert_eq!(rest, payload);
+    }
+
+    #[test]
+    fn leaves_payload_without_metadata_unframed() {
+        let payload = Bytes::from_static(b"\x0a\x03abc");
+        assert_eq!(encode(&[], payload.clone()), payload);
+        let (metadata, rest) = decode(payload.clone()).unwrap();
+        assert!(metadata.is_empty());
+        assert_eq!(rest, payload);
+    }
+
+    #[test]
+    fn passes_through_empty_payload() {
+        let (metadata, rest) = decode(Bytes::new()).unwrap();
+        assert!(metadata.is_empty());
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_frames() {
+        let framed = encode(
+            &entries(&[("key", "value")]),
+            Bytes::from_static(b"payload"),
+        );
+        let header_len = MAGIC.len() + 2 + 2 + 3 + 4 + 5;
+        for len in MAGIC.len()..header_len {
+            let e = decode(framed.slice(..len)).unwrap_err();
+            assert!(crate::error::has_code(&e, ErrorCode::Deserialization));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_utf8_keys() {
+        let mut buf = BytesMut::new();
+        buf.put_slice(&MAGIC);
+        buf.put_u16(1);
+        buf.put_u16(1);
+        buf.put_u8(0xff);
+        buf.put_u32(0);
+        assert!(decode(buf.freeze()).is_err());
+    }
+}
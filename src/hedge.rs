@@ -0,0 +1,382 @@
+// Hedged requests
+//
+// Calls to routes listed in `WorkloadOptions.hedgeRoutes` (which marks them idempotent) are sent
+// again to another candidate of the target's type when they have not answered within a delay.
+// The first response wins and the other attempts are cancelled. An attempt failing with
+// `UNAVAILABLE`, `TRANSPORT` or `TIMEOUT` sends the next hedge at once; any other error is the
+// call's answer. The delay is a percentile of the route's recent response times, or `delayMs`
+// until `minSamples` responses have been seen.
+// Only calls made through a handler's context are hedged.
+
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::error::{ErrorCode, actr_error};
+use crate::prefixes::RoutePrefixes;
+
+const DEFAULT_PERCENTILE: f64 = 95.0;
+const DEFAULT_DELAY_MS: u32 = 100;
+const DEFAULT_MIN_SAMPLES: u32 = 20;
+const DEFAULT_MAX_HEDGES: u32 = 1;
+
+/// Response times kept per route.
+const LATENCY_WINDOW: usize = 128;
+
+/// When and how often a call to an idempotent route is hedged.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct HedgePolicy {
+    /// Percentile of recent response times after which a hedge is sent (default 95).
+    pub percentile: Option<f64>,
+    /// Delay before a hedge while too few response times are known, in ms (default 100).
+    #[napi(js_name = "delayMs")]
+    pub delay_ms: Option<u32>,
+    /// Response times needed before the percentile is used (default 20).
+    #[napi(js_name = "minSamples")]
+    pub min_samples: Option<u32>,
+    /// Extra copies sent at most, each after another delay (default 1).
+    #[napi(js_name = "maxHedges")]
+    pub max_hedges: Option<u32>,
+}
+
+/// How one call is hedged.
+pub struct Hedge {
+    delay: Duration,
+    max_hedges: usize,
+}
+
+impl Hedge {
+    pub fn max_hedges(&self) -> usize {
+        self.max_hedges
+    }
+}
+
+/// Hedged routes of a node and their recent response times.
+#[derive(Default)]
+pub struct Hedging {
+    routes: RoutePrefixes<HedgePolicy>,
+    latencies: Mutex<HashMap<String, VecDeque<Duration>>>,
+}
+
+impl Hedging {
+    pub fn new(routes: Option<HashMap<String, HedgePolicy>>) -> Self {
+        Self {
+            routes: routes.into_iter().flatten().collect(),
+            latencies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// How a call to `route_key` is hedged, or `None` when the route is not hedged.
+    pub fn plan(&self, route_key: &str) -> Option<Hedge> {
+        let policy = self.routes.find(route_key)?;
+        let max_hedges = policy.max_hedges.unwrap_or(DEFAULT_MAX_HEDGES) as usize;
+        if max_hedges == 0 {
+            return None;
+        }
+
+        let fallback = Duration::from_millis(policy.delay_ms.unwrap_or(DEFAULT_DELAY_MS) as u64);
+        let min_samples = policy.min_samples.unwrap_or(DEFAULT_MIN_SAMPLES).max(1) as usize;
+        let percentile = policy
+            .percentile
+            .unwrap_or(DEFAULT_PERCENTILE)
+            .clamp(0.0, 100.0);
+        let delay = match self.latencies.lock().get(route_key) {
+            Some(samples) if samples.len() >= min_samples => {
+                let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+                sorted.sort();
+                let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+                sorted[rank.clamp(1, sorted.len()) - 1]
+            }
+            _ => fallback,
+        };
+        Some(Hedge { delay, max_hedges })
+    }
+
+    /// Record the response time of a successful call to a hedged route.
+    pub fn record(&self, route_key: &str, elapsed: Duration) {
+        if self.routes.find(route_key).is_none() {
+            return;
+        }
+        let mut latencies = self.latencies.lock();
+        let samples = latencies.entry(route_key.to_string()).or_default();
+        if samples.len() == LATENCY_WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back(elapsed);
+    }
+}
+
+/// Errors after which another target may still answer, so the next hedge is sent right away.
+const HEDGED_ERRORS: [ErrorCode; 3] = [
+    ErrorCode::Unavailable,
+    ErrorCode::Transport,
+    ErrorCode::Timeout,
+];
+
+fn starts_hedge(e: &napi::Error) -> bool {
+    HEDGED_ERRORS
+        .iter()
+        .any(|code| crate::error::has_code(e, *code))
+}
+
+/// Call `targets[0]`, sending the call to the next target each time `hedge`'s delay passes
+/// without a response, or as soon as an attempt fails with one of `HEDGED_ERRORS`. Resolves with
+/// the first success, the first other error, or the last error once every attempt has failed.
+///
+/// Each attempt gets a cancellation receiver that fires once the outcome is known or `cancel`
+/// fires; the losing attempts are awaited so they can observe it.
+pub async fn run<T, F, Fut>(
+    hedge: &Hedge,
+    targets: Vec<actr_protocol::ActrId>,
+    cancel: Option<watch::Receiver<bool>>,
+    mut attempt: F,
+) -> napi::Result<T>
+where
+    F: FnMut(actr_protocol::ActrId, watch::Receiver<bool>) -> Fut,
+    Fut: Future<Output = napi::Result<T>>,
+{
+    let (attempts_cancel_tx, attempts_cancel) = watch::channel(false);
+    let mut targets = targets.into_iter();
+    let Some(primary) = targets.next() else {
+        return Err(actr_error(ErrorCode::NoCandidates, "no target to call"));
+    };
+    let mut hedges = targets.take(hedge.max_hedges);
+
+    let mut pending = FuturesUnordered::new();
+    pending.push(attempt(primary, attempts_cancel.clone()));
+    let timer = tokio::time::sleep(hedge.delay);
+    tokio::pin!(timer);
+    let caller_cancel = async {
+        match cancel {
+            Some(rx) => crate::cancel::cancelled(rx).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(caller_cancel);
+    let mut hedging = true;
+    let mut cancelled = false;
+
+    let result = loop {
+        tokio::select! {
+            Some(result) = pending.next() => match result {
+                Err(e) if !cancelled && starts_hedge(&e) => match hedges.next() {
+                    Some(target) => {
+                        tracing::debug!(
+                            "hedging call to {} after a failed attempt: {e}",
+                            crate::identity::id_to_string(&target)
+                        );
+                        pending.push(attempt(target, attempts_cancel.clone()));
+                        timer.as_mut().reset(tokio::time::Instant::now() + hedge.delay);
+                    }
+                    None if !pending.is_empty() => {
+                        hedging = false;
+                        tracing::debug!("hedged attempt failed: {e}");
+                    }
+                    None => break Err(e),
+                },
+                Err(e) if cancelled && !pending.is_empty() => {
+                    tracing::debug!("hedged attempt failed: {e}");
+                }
+                result => break result,
+            },
+            _ = &mut timer, if hedging => match hedges.next() {
+                Some(target) => {
+                    tracing::debug!(
+                        "hedging call to {} after {}ms",
+                        crate::identity::id_to_string(&target),
+                        hedge.delay.as_millis()
+                    );
+                    pending.push(attempt(target, attempts_cancel.clone()));
+                    timer.as_mut().reset(tokio::time::Instant::now() + hedge.delay);
+                }
+                None => hedging = false,
+            },
+            _ = &mut caller_cancel, if !cancelled => {
+                cancelled = true;
+                attempts_cancel_tx.send_replace(true);
+            }
+        }
+    };
+
+    attempts_cancel_tx.send_replace(true);
+    while pending.next().await.is_some() {}
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hedging(policy: HedgePolicy) -> Hedging {
+        Hedging::new(Some(HashMap::from([("cache.".to_string(), policy)])))
+    }
+
+    fn targets(count: u64) -> Vec<actr_protocol::ActrId> {
+        (1..=count)
+            .map(|serial| crate::identity::parse_id(&format!("{serial}@1/acme+cache")).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn uses_the_fallback_delay_until_enough_samples() {
+        let hedging = hedging(HedgePolicy {
+            delay_ms: Some(70),
+            min_samples: Some(3),
+            ..Default::default()
+        });
+        assert!(hedging.plan("echo.Echo").is_none());
+        hedging.record("cache.Get", Duration::from_millis(5));
+        hedging.record("cache.Get", Duration::from_millis(5));
+        assert_eq!(
+            hedging.plan("cache.Get").unwrap().delay,
+            Duration::from_millis(70)
+        );
+    }
+
+    #[test]
+    fn delays_by_the_percentile_of_recent_responses() {
+        let hedging = hedging(HedgePolicy {
+            percentile: Some(90.0),
+            min_samples: Some(10),
+            ..Default::default()
+        });
+        for ms in (1..=10).rev() {
+            hedging.record("cache.Get", Duration::from_millis(ms * 10));
+        }
+        assert_eq!(
+            hedging.plan("cache.Get").unwrap().delay,
+            Duration::from_millis(90)
+        );
+        assert_eq!(
+            hedging.plan("cache.Set").unwrap().delay,
+            Duration::from_millis(DEFAULT_DELAY_MS as u64)
+        );
+    }
+
+    #[test]
+    fn keeps_a_bounded_window_of_samples() {
+        let hedging = hedging(HedgePolicy {
+            percentile: Some(0.0),
+            min_samples: Some(1),
+            ..Default::default()
+        });
+        for ms in 0..(LATENCY_WINDOW as u64 + 10) {
+            hedging.record("cache.Get", Duration::from_millis(ms));
+        }
+        assert_eq!(
+            hedging.plan("cache.Get").unwrap().delay,
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn zero_hedges_disables_hedging() {
+        let hedging = hedging(HedgePolicy {
+            max_hedges: Some(0),
+            ..Default::default()
+        });
+        assert!(hedging.plan("cache.Get").is_none());
+    }
+
+    #[tokio::test]
+    async fn hedges_right_away_when_the_primary_fails() {
+        let hedge = Hedge {
+            delay: Duration::from_secs(60),
+            max_hedges: 1,
+        };
+        let targets = targets(2);
+        let started = tokio::time::Instant::now();
+        let result = run(&hedge, targets.clone(), None, |target, _| {
+            let primary = target == targets[0];
+            async move {
+                if primary {
+                    Err(actr_error(ErrorCode::Unavailable, "down"))
+                } else {
+                    Ok(target)
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, targets[1]);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn fails_with_the_last_error_once_every_attempt_failed() {
+        let hedge = Hedge {
+            delay: Duration::from_secs(60),
+            max_hedges: 1,
+        };
+        let e = run(&hedge, targets(3), None, |_, _| async {
+            Err::<(), _>(actr_error(ErrorCode::Unavailable, "down"))
+        })
+        .await
+        .unwrap_err();
+        assert!(crate::error::has_code(&e, ErrorCode::Unavailable));
+    }
+
+    #[tokio::test]
+    async fn returns_other_errors_without_waiting_for_hedges() {
+        let hedge = Hedge {
+            delay: Duration::from_millis(10),
+            max_hedges: 1,
+        };
+        let targets = targets(2);
+        let started = tokio::time::Instant::now();
+        let e = run(&hedge, targets.clone(), None, |target, mut cancel| {
+            let primary = target == targets[0];
+            async move {
+                if primary {
+                    // Fail once the hedge has been sent, so it is pending.
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    return Err(actr_error(ErrorCode::Application, "rejected"));
+                }
+                let _ = cancel.wait_for(|cancelled| *cancelled).await;
+                Ok(())
+            }
+        })
+        .await
+        .unwrap_err();
+        assert!(crate::error::has_code(&e, ErrorCode::Application));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        let sent = std::sync::atomic::AtomicUsize::new(0);
+        let e = run(&hedge, targets.clone(), None, |_, _| {
+            sent.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async { Err::<(), _>(actr_error(ErrorCode::Application, "rejected")) }
+        })
+        .await
+        .unwrap_err();
+        assert!(crate::error::has_code(&e, ErrorCode::Application));
+        assert_eq!(sent.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn cancels_the_slower_attempt() {
+        let hedge = Hedge {
+            delay: Duration::from_millis(10),
+            max_hedges: 1,
+        };
+        let targets = targets(2);
+        let result = run(&hedge, targets.clone(), None, |target, mut cancel| {
+            let primary = target == targets[0];
+            async move {
+                if primary {
+                    let _ = cancel.wait_for(|cancelled| *cancelled).await;
+                    return Err(actr_error(ErrorCode::Aborted, "cancelled"));
+                }
+                Ok(target)
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, targets[1]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Canonical textual forms for actor identities.
+//!
+//! - `ActrType`: `<manufacturer>+<name>` (e.g. `acme+EchoService`)
+//! - `ActrId`: `<serialNumber>@<realmId>/<manufacturer>+<name>` (e.g. `42@1/acme+EchoService`)
+//!
+//! Both forms round-trip through their parser.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+
+use crate::error::{ErrorCode, actr_error};
+use crate::types::{ActrId, ActrType, SerialNumberMode};
+
+/// Format an `actr_protocol::ActrType` in canonical form.
+pub(crate) fn type_to_string(t: &actr_protocol::ActrType) -> String {
+    format!("{}+{}", t.manufacturer, t.name)
+}
+
+/// Format an `actr_protocol::ActrId` in canonical form.
+pub(crate) fn id_to_string(id: &actr_protocol::ActrId) -> String {
+    format!(
+        "{}@{}/{}",
+        id.serial_number,
+        id.realm.realm_id,
+        type_to_string(&id.r#type)
+    )
+}
+
+fn validate_type(t: &actr_protocol::ActrType) -> Result<()> {
+    let invalid = |reason: &str| {
+        actr_error(
+            ErrorCode::InvalidArgument,
+            format!("invalid ActrType {:?}: {reason}", type_to_string(t)),
+        )
+    };
+
+    if t.manufacturer.is_empty() {
+        return Err(invalid("manufacturer is empty"));
+    }
+    if t.name.is_empty() {
+        return Err(invalid("name is empty"));
+    }
+    if t.manufacturer.contains(['+', '@', '/']) {
+        return Err(invalid("manufacturer must not contain '+', '@' or '/'"));
+    }
+    if t.name.contains(['@', '/']) {
+        return Err(invalid("name must not contain '@' or '/'"));
+    }
+    if type_to_string(t).contains(char::is_whitespace) {
+        return Err(invalid("whitespace is not allowed"));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_type(s: &str) -> Result<actr_protocol::ActrType> {
+    let (manufacturer, name) = s.split_once('+').ok_or_else(|| {
+        actr_error(
+            ErrorCode::InvalidArgument,
+            format!("invalid ActrType {s:?}: expected <manufacturer>+<name>"),
+        )
+    })?;
+    let t = actr_protocol::ActrType {
+        manufacturer: manufacturer.to_string(),
+        name: name.to_string(),
+    };
+    validate_type(&t)?;
+    Ok(t)
+}
+
+pub(crate) fn parse_id(s: &str) -> Result<actr_protocol::ActrId> {
+    let invalid = |reason: &str| {
+        actr_error(
+            ErrorCode::InvalidArgument,
+            format!("invalid ActrId {s:?}: {reason}"),
+        )
+    };
+
+    let (serial, rest) = s
+        .split_once('@')
+        .ok_or_else(|| invalid("expected <serialNumber>@<realmId>/<manufacturer>+<name>"))?;
+    let (realm, actr_type) = rest
+        .split_once('/')
+        .ok_or_else(|| invalid("expected <serialNumber>@<realmId>/<manufacturer>+<name>"))?;
+
+    let serial_number = serial
+        .parse::<u64>()
+        .map_err(|_| invalid("serialNumber is not an unsigned 64-bit integer"))?;
+    let realm_id = realm
+        .parse::<u32>()
+        .map_err(|_| invalid("realmId is not an unsigned 32-bit integer"))?;
+
+    Ok(actr_protocol::ActrId {
+        realm: actr_protocol::Realm { realm_id },
+        serial_number,
+        r#type: parse_type(actr_type)?,
+    })
+}
+
+/// Format an ActrType as `<manufacturer>+<name>`.
+#[napi]
+pub fn format_actr_type(actr_type: ActrType) -> Result<String> {
+    let t: actr_protocol::ActrType = actr_type.into();
+    validate_type(&t)?;
+    Ok(type_to_string(&t))
+}
+
+/// Parse `<manufacturer>+<name>` into an ActrType.
+#[napi]
+pub fn parse_actr_type(s: String) -> Result<ActrType> {
+    Ok(parse_type(&s)?.into())
+}
+
+/// Format an ActrId as `<serialNumber>@<realmId>/<manufacturer>+<name>`.
+///
+/// The result is unique per actor and can be used as a `Map` key.
+#[napi]
+pub fn format_actr_id(id: ActrId) -> Result<String> {
+    let id: actr_protocol::ActrId = id.try_into()?;
+    validate_type(&id.r#type)?;
+    Ok(id_to_string(&id))
+}
+
+/// Parse `<serialNumber>@<realmId>/<manufacturer>+<name>` into an ActrId.
+///
+/// The serial number is a `bigint` unless `mode` is `SerialNumberMode.Number`; pass the mode of
+/// the system the ID is used with.
+#[napi]
+pub fn parse_actr_id(s: String, mode: Option<SerialNumberMode>) -> Result<ActrId> {
+    Ok(mode
+        .unwrap_or(SerialNumberMode::BigInt)
+        .id_to_js(parse_id(&s)?))
+}
+
+/// Compare two ActrTypes for equality.
+#[napi]
+pub fn actr_type_equals(a: ActrType, b: ActrType) -> bool {
+    a.manufacturer == b.manufacturer && a.name == b.name
+}
+
+/// Compare two ActrIds for equality, regardless of how `serialNumber` is represented.
+#[napi]
+pub fn actr_id_equals(a: ActrId, b: ActrId) -> Result<bool> {
+    let a: actr_protocol::ActrId = a.try_into()?;
+    let b: actr_protocol::ActrId = b.try_into()?;
+    Ok(a == b)
+}
+
+/// 32-bit hash of an ActrId, equal for IDs that `actrIdEquals` considers equal and the same in
+/// every process.
+///
+/// Suited to bucketing and sharding; use `formatActrId` as the `Map` key itself.
+#[napi]
+pub fn hash_actr_id(id: ActrId) -> Result<u32> {
+    let id: actr_protocol::ActrId = id.try_into()?;
+    validate_type(&id.r#type)?;
+    Ok(crate::hashring::hash([id_to_string(&id).as_bytes()]) as u32)
+}
@@ -0,0 +1,359 @@
+// Stream registration leases
+//
+// Every stream handler registered through `ctx.registerStream` or `ctx.receiveStream` holds a
+// lease, supervised by a task that unregisters the handler once the stream is over: when the
+// sender ends or aborts it, when it is closed or unregistered on this side, when no chunk arrives
+// within `idleTimeoutMs`, when the sender is no longer registered, or when the workload stops.
+// The close is then reported once, with its reason, so neither the handler nor its JS callback
+// outlives the stream.
+//
+// The runtime does not report peers going away, so with `peerCheckMs` the sender's type is
+// re-discovered periodically. The sender counts as gone only once an earlier check has found it
+// and a complete result (or `NOT_FOUND`) no longer does; senders that are not discoverable, such
+// as clients, are never closed this way.
+
+use actr_framework::Context;
+use actr_runtime::context::RuntimeContext;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::error::ErrorCode;
+use crate::routing::Router;
+use crate::types::ActrId;
+
+/// Why a stream registration was closed.
+#[napi]
+#[derive(PartialEq, Eq)]
+pub enum StreamCloseReason {
+    /// The sender called `end()`.
+    Ended,
+    /// The sender called `abort()`.
+    Aborted,
+    /// Closed on this side: `close()`, `unregisterStream` or a new registration of the same
+    /// stream ID.
+    Unregistered,
+    /// No chunk arrived within `idleTimeoutMs`.
+    IdleTimeout,
+    /// The sender is no longer registered.
+    PeerDisconnected,
+    /// The workload stopped.
+    WorkloadStopped,
+    /// A chunk arrived while the receiver's buffer was full and its overflow policy is `Error`.
+    Overflowed,
+}
+
+/// Options for `ctx.registerStream`.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct StreamRegistrationOptions {
+    /// Close the registration when no chunk arrives for this long, in ms (default: never).
+    #[napi(js_name = "idleTimeoutMs")]
+    pub idle_timeout_ms: Option<u32>,
+    /// How often the sender is checked to still be registered, in ms (default: never).
+    #[napi(js_name = "peerCheckMs")]
+    pub peer_check_ms: Option<u32>,
+}
+
+/// Final event of a stream registration.
+#[napi(object)]
+pub struct StreamClosed {
+    #[napi(js_name = "streamId")]
+    pub stream_id: String,
+    pub reason: StreamCloseReason,
+    /// Sender of the last chunk, if any arrived.
+    pub sender: Option<ActrId>,
+}
+
+/// When a registration closes by itself.
+#[derive(Clone, Copy)]
+pub struct Lifetime {
+    idle: Option<Duration>,
+    peer_check: Option<Duration>,
+}
+
+impl Lifetime {
+    pub fn new(idle_timeout_ms: Option<u32>, peer_check_ms: Option<u32>) -> Self {
+        Self {
+            idle: idle_timeout_ms
+                .filter(|ms| *ms > 0)
+                .map(|ms| Duration::from_millis(ms as u64)),
+            peer_check: peer_check_ms
+                .filter(|ms| *ms > 0)
+                .map(|ms| Duration::from_millis(ms as u64)),
+        }
+    }
+}
+
+struct Activity {
+    last: Instant,
+    sender: Option<actr_protocol::ActrId>,
+}
+
+/// A stream handler registered with the runtime, closed at most once.
+pub struct Lease {
+    stream_id: String,
+    id: u64,
+    activity: Mutex<Activity>,
+    reason: watch::Sender<Option<StreamCloseReason>>,
+    /// Flipped once the handler is unregistered and the close reported.
+    done: watch::Sender<bool>,
+}
+
+impl Lease {
+    /// Record a chunk from `sender`.
+    pub fn touch(&self, sender: &actr_protocol::ActrId) {
+        let mut activity = self.activity.lock();
+        activity.last = Instant::now();
+        if activity.sender.as_ref() != Some(sender) {
+            activity.sender = Some(sender.clone());
+        }
+    }
+
+    /// Close the registration for `reason`, unless it is already closing.
+    pub fn close(&self, reason: StreamCloseReason) {
+        self.reason.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason);
+            true
+        });
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.reason.borrow().is_some()
+    }
+
+    /// Why the registration closed, once it has.
+    pub fn reason(&self) -> Option<StreamCloseReason> {
+        *self.reason.borrow()
+    }
+
+    /// Resolve once the handler is unregistered and the close reported.
+    pub async fn closed(&self) {
+        let _ = self.done.subscribe().wait_for(|done| *done).await;
+    }
+
+    fn sender(&self) -> Option<actr_protocol::ActrId> {
+        self.activity.lock().sender.clone()
+    }
+}
+
+/// Live stream registrations of a node, by stream ID.
+#[derive(Default)]
+pub struct Leases {
+    next_id: AtomicU64,
+    live: Mutex<HashMap<String, Arc<Lease>>>,
+}
+
+impl Leases {
+    /// A new lease for a handler of `stream_id`, tracked once `supervise` starts.
+    pub fn lease(&self, stream_id: &str) -> Arc<Lease> {
+        Arc::new(Lease {
+            stream_id: stream_id.to_string(),
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            activity: Mutex::new(Activity {
+                last: Instant::now(),
+                sender: None,
+            }),
+            reason: watch::channel(None).0,
+            done: watch::channel(false).0,
+        })
+    }
+
+    /// Stop tracking the lease of `stream_id` and return it, e.g. because its handler is being
+    /// unregistered directly.
+    pub fn take(&self, stream_id: &str) -> Option<Arc<Lease>> {
+        self.live.lock().remove(stream_id)
+    }
+
+    /// Track `lease` as the handler of its stream, closing the lease it replaces.
+    fn insert(&self, lease: Arc<Lease>) {
+        let replaced = self.live.lock().insert(lease.stream_id.clone(), lease);
+        if let Some(replaced) = replaced {
+            replaced.close(StreamCloseReason::Unregistered);
+        }
+    }
+
+    /// Stop tracking `lease`; false when its stream has since been unregistered or registered
+    /// again, so the handler now registered is not its own.
+    fn remove(&self, lease: &Lease) -> bool {
+        let mut live = self.live.lock();
+        match live.get(&lease.stream_id) {
+            Some(current) if current.id == lease.id => {
+                live.remove(&lease.stream_id);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Watch `lease`, whose handler has just been registered on `ctx`, until it closes; then
+/// unregister the handler and call `on_close` with the reason and the last sender.
+///
+/// Must be called from within the tokio runtime.
+pub fn supervise(
+    router: Arc<Router>,
+    ctx: RuntimeContext,
+    lease: Arc<Lease>,
+    lifetime: Lifetime,
+    on_close: impl FnOnce(StreamCloseReason, Option<actr_protocol::ActrId>) + Send + 'static,
+) {
+    router.streams().insert(lease.clone());
+    tokio::spawn(async move {
+        let mut reason_rx = lease.reason.subscribe();
+        let check_every = lifetime.peer_check.unwrap_or(Duration::from_secs(3600));
+        let mut peer_checks = tokio::time::interval_at(Instant::now() + check_every, check_every);
+        peer_checks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut seen = None;
+
+        let reason = loop {
+            let idle_at = lifetime.idle.map(|idle| lease.activity.lock().last + idle);
+            tokio::select! {
+                reason = reason_rx.wait_for(Option::is_some) => {
+                    break reason
+                        .ok()
+                        .and_then(|reason| *reason)
+                        .unwrap_or(StreamCloseReason::Unregistered);
+                }
+                _ = router.closed() => break StreamCloseReason::WorkloadStopped,
+                _ = sleep_until(idle_at) => {
+                    if lifetime
+                        .idle
+                        .is_some_and(|idle| lease.activity.lock().last.elapsed() >= idle)
+                    {
+                        break StreamCloseReason::IdleTimeout;
+                    }
+                }
+                _ = peer_checks.tick(), if lifetime.peer_check.is_some() => {
+                    if let Some(sender) = lease.sender()
+                        && peer_left(&router, &sender, &mut seen).await
+                    {
+                        break StreamCloseReason::PeerDisconnected;
+                    }
+                }
+            }
+        };
+
+        lease.close(reason);
+        let reason = lease.reason().unwrap_or(reason);
+        if router.streams().remove(&lease)
+            && let Err(e) = ctx.unregister_stream(&lease.stream_id).await
+        {
+            tracing::debug!("failed to unregister stream {}: {e}", lease.stream_id);
+        }
+        tracing::debug!("stream {} closed", lease.stream_id);
+        on_close(reason, lease.sender());
+        lease.done.send_replace(true);
+    });
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Whether `peer` has left: an earlier check recorded it in `seen`, and now its type was
+/// discovered completely without it, or not found at all.
+async fn peer_left(
+    router: &Router,
+    peer: &actr_protocol::ActrId,
+    seen: &mut Option<actr_protocol::ActrId>,
+) -> bool {
+    let count = router.discovery_count();
+    let missing = match router.discover(None, &peer.r#type, count).await {
+        Ok(ids) if ids.contains(peer) => {
+            *seen = Some(peer.clone());
+            return false;
+        }
+        Ok(ids) => ids.len() < count as usize,
+        Err(e) => crate::error::has_code(&e, ErrorCode::NotFound),
+    };
+    missing && seen.as_ref() == Some(peer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_close_reason_wins() {
+        let lease = Leases::default().lease("s");
+        assert!(!lease.is_closed());
+        lease.close(StreamCloseReason::Ended);
+        lease.close(StreamCloseReason::IdleTimeout);
+        assert!(lease.is_closed());
+        assert!(lease.reason() == Some(StreamCloseReason::Ended));
+    }
+
+    #[test]
+    fn new_registration_closes_the_one_it_replaces() {
+        let leases = Leases::default();
+        let (first, second) = (leases.lease("s"), leases.lease("s"));
+        leases.insert(first.clone());
+        leases.insert(second.clone());
+        assert!(first.reason() == Some(StreamCloseReason::Unregistered));
+        assert!(!second.is_closed());
+
+        // The replaced lease must not unregister the handler that replaced it.
+        assert!(!leases.remove(&first));
+        assert!(leases.remove(&second));
+        assert!(leases.take("s").is_none());
+    }
+
+    #[test]
+    fn take_stops_tracking_a_lease() {
+        let leases = Leases::default();
+        let lease = leases.lease("s");
+        leases.insert(lease.clone());
+        assert!(
+            leases
+                .take("s")
+                .is_some_and(|taken| Arc::ptr_eq(&taken, &lease))
+        );
+        assert!(!leases.remove(&lease));
+    }
+
+    #[test]
+    fn touch_records_the_last_sender() {
+        let lease = Leases::default().lease("s");
+        let before = lease.activity.lock().last;
+        let sender = crate::identity::parse_id("1@1/acme+echo").unwrap();
+        lease.touch(&sender);
+        assert_eq!(lease.sender(), Some(sender));
+        assert!(lease.activity.lock().last >= before);
+    }
+
+    #[test]
+    fn peer_checks_are_opt_in() {
+        assert!(Lifetime::new(None, None).peer_check.is_none());
+        assert!(Lifetime::new(None, Some(0)).peer_check.is_none());
+        assert_eq!(
+            Lifetime::new(Some(100), Some(250)).peer_check,
+            Some(Duration::from_millis(250))
+        );
+    }
+
+    #[tokio::test]
+    async fn closed_resolves_once_done() {
+        let lease = Leases::default().lease("s");
+        lease.close(StreamCloseReason::Aborted);
+        let waiter = {
+            let lease = lease.clone();
+            tokio::spawn(async move { lease.closed().await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        lease.done.send_replace(true);
+        waiter.await.unwrap();
+    }
+}
@@ -1,14 +1,33 @@
 #![deny(clippy::all)]
 
+mod breaker;
+mod cache;
+mod cancel;
+mod coalesce;
 mod context;
 mod error;
+mod hashring;
+mod headers;
+mod hedge;
+mod identity;
+mod lease;
 mod logger;
+mod prefixes;
+mod retry;
+mod routing;
 mod runtime;
+mod stream;
+mod tags;
 mod types;
+mod watch;
 mod workload;
 
 // Re-export modules
+pub use cancel::CancellationToken;
 pub use context::*;
+pub use identity::*;
 pub use runtime::*;
+pub use stream::{StreamReceiver, StreamWriter};
 pub use types::*;
+pub use watch::DiscoveryWatch;
 pub use workload::*;
@@ -0,0 +1,59 @@
+import { describe, it, expect, vi } from 'vitest';
+import { withCallOptions } from '../typescript/options';
+
+const tokens = vi.hoisted(() => [] as Array<{ id: number; cancelled: boolean; disposed: boolean }>);
+
+vi.mock('../index', () => ({
+  CancellationToken: class {
+    id = tokens.length + 1;
+    cancelled = false;
+    disposed = false;
+    constructor() {
+      tokens.push(this);
+    }
+    cancel() {
+      this.cancelled = true;
+    }
+    dispose() {
+      this.disposed = true;
+    }
+  },
+}));
+
+const last = () => tokens[tokens.length - 1];
+
+describe('withCallOptions', () => {
+  it('should pass the token id and release the token once the call settles', async () => {
+    const controller = new AbortController();
+    const result = await withCallOptions(
+      { signal: controller.signal, timeoutMs: 5 },
+      async (native) => {
+        expect(native).toEqual({ timeoutMs: 5, cancelTokenId: last().id });
+        return 'ok';
+      }
+    );
+    expect(result).toBe('ok');
+    expect(last().disposed).toBe(true);
+
+    controller.abort();
+    expect(last().cancelled).toBe(false);
+  });
+
+  it('should release the token when the call fails', async () => {
+    const controller = new AbortController();
+    await expect(
+      withCallOptions({ signal: controller.signal }, async () => {
+        throw new Error('down');
+      })
+    ).rejects.toThrow('down');
+    expect(last().disposed).toBe(true);
+  });
+
+  it('should cancel the token when the signal aborts during the call', async () => {
+    const controller = new AbortController();
+    await withCallOptions({ signal: controller.signal }, async () => {
+      controller.abort();
+    });
+    expect(last().cancelled).toBe(true);
+  });
+});
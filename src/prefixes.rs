@@ -0,0 +1,65 @@
+// Route-key prefix tables
+//
+// Retry, hedging and response-cache settings are configured per route-key prefix. A route takes
+// the settings of its longest matching prefix; prefixes of equal length are ordered by name so
+// the order does not depend on how the table was built.
+
+/// Values keyed by route-key prefix, matched by the longest prefix of a route key.
+pub struct RoutePrefixes<T> {
+    /// Sorted by descending prefix length so the first match is the longest.
+    entries: Vec<(String, T)>,
+}
+
+impl<T> RoutePrefixes<T> {
+    pub fn new(entries: impl IntoIterator<Item = (String, T)>) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        Self { entries }
+    }
+
+    /// Value of the longest prefix of `route_key`, if any.
+    pub fn find(&self, route_key: &str) -> Option<&T> {
+        self.entries
+            .iter()
+            .find(|(prefix, _)| route_key.starts_with(prefix.as_str()))
+            .map(|(_, value)| value)
+    }
+}
+
+impl<T> Default for RoutePrefixes<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(String, T)> for RoutePrefixes<T> {
+    fn from_iter<I: IntoIterator<Item = (String, T)>>(entries: I) -> Self {
+        Self::new(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_longest_matching_prefix() {
+        let prefixes: RoutePrefixes<u32> = [
+            ("catalog.".to_string(), 1),
+            ("catalog.Get".to_string(), 2),
+            (String::new(), 3),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(prefixes.find("catalog.GetItem"), Some(&2));
+        assert_eq!(prefixes.find("catalog.List"), Some(&1));
+        assert_eq!(prefixes.find("orders.Place"), Some(&3));
+        assert_eq!(
+            RoutePrefixes::<u32>::default().find("catalog.GetItem"),
+            None
+        );
+    }
+}
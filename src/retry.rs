@@ -0,0 +1,491 @@
+// Retry policies for outbound calls
+//
+// A policy is resolved per call from, in order: `CallOptions.retry`, the longest matching
+// route-key prefix in `WorkloadOptions.retryRoutes`, and `WorkloadOptions.retry`. Without any of
+// them a call is attempted once. The `[retry]` table of `Actr.toml` (and its `[retry.routes]`
+// sub-table) supplies node policies the attach options do not set.
+//
+// Policies are validated when they are first seen, so an unknown `retryOn` code is rejected with
+// `INVALID_ARGUMENT` rather than silently never matching.
+
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::error::{ErrorCode, actr_error};
+use crate::prefixes::RoutePrefixes;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF_MS: u32 = 100;
+const DEFAULT_MAX_BACKOFF_MS: u32 = 5_000;
+const DEFAULT_MULTIPLIER: f64 = 2.0;
+const DEFAULT_JITTER: f64 = 0.2;
+const DEFAULT_RETRY_ON: [ErrorCode; 2] = [ErrorCode::Unavailable, ErrorCode::Transport];
+
+/// Table of `Actr.toml` read as the node's retry policies.
+const CONFIG_TABLE: &str = "retry";
+/// Sub-table of `[retry]` holding policies by route-key prefix.
+const CONFIG_ROUTES: &str = "routes";
+
+/// How failed calls are retried.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct RetryPolicy {
+    /// Total attempts including the first (default 3).
+    #[napi(js_name = "maxAttempts")]
+    pub max_attempts: Option<u32>,
+    /// Pause before the first retry, in ms (default 100).
+    #[napi(js_name = "initialBackoffMs")]
+    pub initial_backoff_ms: Option<u32>,
+    /// Longest pause between attempts, in ms (default 5000).
+    #[napi(js_name = "maxBackoffMs")]
+    pub max_backoff_ms: Option<u32>,
+    /// Growth of the pause per retry (default 2).
+    pub multiplier: Option<f64>,
+    /// Fraction of each pause that is randomized, 0 to 1 (default 0.2).
+    pub jitter: Option<f64>,
+    /// Error codes that are retried (default `UNAVAILABLE` and `TRANSPORT`). `ABORTED` never is.
+    #[napi(js_name = "retryOn")]
+    pub retry_on: Option<Vec<String>>,
+    /// Time budget for all attempts and pauses together, in ms. Rejects with `TIMEOUT` once spent.
+    #[napi(js_name = "deadlineMs")]
+    pub deadline_ms: Option<u32>,
+}
+
+/// A `RetryPolicy` with its defaults applied.
+#[derive(Clone)]
+pub struct Retry {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: f64,
+    retry_on: Vec<ErrorCode>,
+    deadline: Option<Duration>,
+}
+
+impl Retry {
+    /// Apply the defaults of `policy`, rejecting unknown `retryOn` codes.
+    pub fn new(policy: &RetryPolicy) -> napi::Result<Self> {
+        let retry_on = match &policy.retry_on {
+            Some(codes) => codes
+                .iter()
+                .map(|code| {
+                    ErrorCode::parse(code).ok_or_else(|| {
+                        actr_error(
+                            ErrorCode::InvalidArgument,
+                            format!("unknown error code {code:?} in retryOn"),
+                        )
+                    })
+                })
+                .collect::<napi::Result<_>>()?,
+            None => DEFAULT_RETRY_ON.to_vec(),
+        };
+        Ok(Self {
+            max_attempts: policy.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS).max(1),
+            initial_backoff: Duration::from_millis(
+                policy
+                    .initial_backoff_ms
+                    .unwrap_or(DEFAULT_INITIAL_BACKOFF_MS) as u64,
+            ),
+            max_backoff: Duration::from_millis(
+                policy.max_backoff_ms.unwrap_or(DEFAULT_MAX_BACKOFF_MS) as u64,
+            ),
+            multiplier: policy.multiplier.unwrap_or(DEFAULT_MULTIPLIER).max(1.0),
+            jitter: policy.jitter.unwrap_or(DEFAULT_JITTER).clamp(0.0, 1.0),
+            retry_on,
+            deadline: policy
+                .deadline_ms
+                .map(|ms| Duration::from_millis(ms as u64)),
+        })
+    }
+
+    /// A single attempt.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MS as u64),
+            max_backoff: Duration::from_millis(DEFAULT_MAX_BACKOFF_MS as u64),
+            multiplier: DEFAULT_MULTIPLIER,
+            jitter: DEFAULT_JITTER,
+            retry_on: DEFAULT_RETRY_ON.to_vec(),
+            deadline: None,
+        }
+    }
+
+    /// Also retry on `codes`.
+    pub fn also_on(mut self, codes: &[ErrorCode]) -> Self {
+        for code in codes {
+            if !self.retry_on.contains(code) {
+                self.retry_on.push(*code);
+            }
+        }
+        self
+    }
+
+    /// Allow at least `attempts` attempts.
+    pub fn at_least(mut self, attempts: u32) -> Self {
+        self.max_attempts = self.max_attempts.max(attempts);
+        self
+    }
+
+    fn is_retryable(&self, e: &napi::Error) -> bool {
+        !crate::error::has_code(e, ErrorCode::Aborted)
+            && self
+                .retry_on
+                .iter()
+                .any(|code| crate::error::has_code(e, *code))
+    }
+
+    /// Pause before retry number `retry` (1-based), with jitter applied.
+    fn backoff(&self, retry: u32) -> Duration {
+        let base = self.initial_backoff.as_secs_f64() * self.multiplier.powi(retry as i32 - 1);
+        let base = base.min(self.max_backoff.as_secs_f64());
+        Duration::from_secs_f64(base * (1.0 - self.jitter * fastrand::f64()))
+    }
+}
+
+/// Node-level retry policies.
+#[derive(Default)]
+pub struct RetryPolicies {
+    default: Option<Retry>,
+    routes: RoutePrefixes<Retry>,
+}
+
+impl RetryPolicies {
+    pub fn new(
+        default: Option<&RetryPolicy>,
+        routes: Option<&HashMap<String, RetryPolicy>>,
+    ) -> napi::Result<Self> {
+        let routes = routes
+            .into_iter()
+            .flatten()
+            .map(|(prefix, policy)| Ok((prefix.clone(), Retry::new(policy)?)))
+            .collect::<napi::Result<Vec<_>>>()?;
+        Ok(Self {
+            default: default.map(Retry::new).transpose()?,
+            routes: RoutePrefixes::new(routes),
+        })
+    }
+
+    /// Policy for a call to `route_key`; `per_call` takes precedence.
+    pub fn resolve(&self, route_key: &str, per_call: Option<&RetryPolicy>) -> napi::Result<Retry> {
+        if let Some(policy) = per_call {
+            return Retry::new(policy);
+        }
+        Ok(self
+            .routes
+            .find(route_key)
+            .or(self.default.as_ref())
+            .cloned()
+            .unwrap_or_else(Retry::none))
+    }
+}
+
+/// Retry policies from the `[retry]` table of a config file: the table itself is the node's
+/// default policy when it sets any field, and `[retry.routes]` holds policies by route-key
+/// prefix. Keys are the `RetryPolicy` fields in snake_case.
+#[derive(Default)]
+pub struct RetryConfig {
+    pub default: Option<RetryPolicy>,
+    pub routes: HashMap<String, RetryPolicy>,
+}
+
+impl RetryConfig {
+    /// Read the `[retry]` table of `path`.
+    pub fn from_config_file(path: &str) -> napi::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| actr_error(ErrorCode::Config, format!("failed to read {path}: {e}")))?;
+        let config: toml::Table = content
+            .parse()
+            .map_err(|e| actr_error(ErrorCode::Config, format!("failed to parse {path}: {e}")))?;
+
+        let Some(table) = config.get(CONFIG_TABLE) else {
+            return Ok(Self::default());
+        };
+        let table = as_table(table, CONFIG_TABLE, path)?;
+        let mut routes = HashMap::new();
+        if let Some(route_tables) = table.get(CONFIG_ROUTES) {
+            let name = format!("{CONFIG_TABLE}.{CONFIG_ROUTES}");
+            for (prefix, policy) in as_table(route_tables, &name, path)? {
+                let name = format!("{name}.{prefix}");
+                let policy = policy_from_table(as_table(policy, &name, path)?, &name, path)?;
+                Retry::new(&policy)?;
+                routes.insert(prefix.clone(), policy);
+            }
+        }
+
+        let mut fields = table.clone();
+        fields.remove(CONFIG_ROUTES);
+        let default = if fields.is_empty() {
+            None
+        } else {
+            let policy = policy_from_table(&fields, CONFIG_TABLE, path)?;
+            Retry::new(&policy)?;
+            Some(policy)
+        };
+        Ok(Self { default, routes })
+    }
+
+    /// Fill in what the attach options leave unset: their default policy wins, and their route
+    /// policies replace those of the same prefix.
+    pub fn apply(
+        self,
+        default: &mut Option<RetryPolicy>,
+        routes: &mut Option<HashMap<String, RetryPolicy>>,
+    ) {
+        if default.is_none() {
+            *default = self.default;
+        }
+        if self.routes.is_empty() {
+            return;
+        }
+        let mut merged = self.routes;
+        merged.extend(routes.take().into_iter().flatten());
+        *routes = Some(merged);
+    }
+}
+
+fn as_table<'a>(value: &'a toml::Value, name: &str, path: &str) -> napi::Result<&'a toml::Table> {
+    value.as_table().ok_or_else(|| {
+        actr_error(
+            ErrorCode::Config,
+            format!("[{name}] in {path} must be a table"),
+        )
+    })
+}
+
+fn policy_from_table(table: &toml::Table, name: &str, path: &str) -> napi::Result<RetryPolicy> {
+    let invalid = |key: &str, expected: &str| {
+        actr_error(
+            ErrorCode::Config,
+            format!("{name}.{key} in {path} must be {expected}"),
+        )
+    };
+    let millis = |key: &str| -> napi::Result<Option<u32>> {
+        table
+            .get(key)
+            .map(|v| {
+                v.as_integer()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .ok_or_else(|| invalid(key, "a non-negative integer"))
+            })
+            .transpose()
+    };
+    let float = |key: &str| -> napi::Result<Option<f64>> {
+        table
+            .get(key)
+            .map(|v| {
+                v.as_float()
+                    .or_else(|| v.as_integer().map(|n| n as f64))
+                    .ok_or_else(|| invalid(key, "a number"))
+            })
+            .transpose()
+    };
+    let retry_on = table
+        .get("retry_on")
+        .map(|v| {
+            v.as_array()
+                .and_then(|codes| {
+                    codes
+                        .iter()
+                        .map(|c| c.as_str().map(str::to_string))
+                        .collect::<Option<Vec<_>>>()
+                })
+                .ok_or_else(|| invalid("retry_on", "an array of strings"))
+        })
+        .transpose()?;
+    Ok(RetryPolicy {
+        max_attempts: millis("max_attempts")?,
+        initial_backoff_ms: millis("initial_backoff_ms")?,
+        max_backoff_ms: millis("max_backoff_ms")?,
+        multiplier: float("multiplier")?,
+        jitter: float("jitter")?,
+        retry_on,
+        deadline_ms: millis("deadline_ms")?,
+    })
+}
+
+/// Run `attempt` until it succeeds, fails with a non-retryable error, or the policy runs out.
+///
+/// Pauses between attempts end early with `ABORTED` when either cancellation receiver fires.
+pub async fn run<T, F, Fut>(
+    retry: &Retry,
+    cancel: (Option<watch::Receiver<bool>>, Option<watch::Receiver<bool>>),
+    mut attempt: F,
+) -> napi::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = napi::Result<T>>,
+{
+    let started = Instant::now();
+    let attempts = async {
+        let mut retries = 0;
+        loop {
+            match attempt().await {
+                Err(e) if retries + 1 < retry.max_attempts && retry.is_retryable(&e) => {
+                    retries += 1;
+                    let backoff = retry.backoff(retries);
+                    tracing::debug!("retrying after {}ms: {e}", backoff.as_millis());
+                    let pause = async {
+                        tokio::time::sleep(backoff).await;
+                        Ok(())
+                    };
+                    crate::cancel::with_cancel(
+                        cancel.0.clone(),
+                        crate::cancel::with_cancel(cancel.1.clone(), pause),
+                    )
+                    .await?;
+                }
+                result => return result,
+            }
+        }
+    };
+
+    match retry.deadline {
+        Some(deadline) => tokio::time::timeout_at(started + deadline, attempts)
+            .await
+            .unwrap_or_else(|_| {
+                Err(actr_error(
+                    ErrorCode::Timeout,
+                    format!("retry deadline of {}ms exceeded", deadline.as_millis()),
+                ))
+            }),
+        None => attempts.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: Some(max_attempts),
+            initial_backoff_ms: Some(0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn backoff_grows_up_to_the_cap() {
+        let retry = Retry::new(&RetryPolicy {
+            initial_backoff_ms: Some(100),
+            max_backoff_ms: Some(500),
+            multiplier: Some(2.0),
+            jitter: Some(0.0),
+            ..Default::default()
+        })
+        .unwrap();
+        let pauses: Vec<u128> = (1..=5).map(|n| retry.backoff(n).as_millis()).collect();
+        assert_eq!(pauses, [100, 200, 400, 500, 500]);
+    }
+
+    #[test]
+    fn jitter_only_shortens_the_pause() {
+        let retry = Retry::new(&RetryPolicy {
+            initial_backoff_ms: Some(100),
+            jitter: Some(0.5),
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..100 {
+            let pause = retry.backoff(1);
+            assert!(pause > Duration::from_millis(50) && pause <= Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_retry_on_codes() {
+        let e = Retry::new(&RetryPolicy {
+            retry_on: Some(vec!["UNAVAILABLE".into(), "UNAVAILBLE".into()]),
+            ..Default::default()
+        })
+        .err()
+        .unwrap();
+        assert!(crate::error::has_code(&e, ErrorCode::InvalidArgument));
+    }
+
+    #[test]
+    fn retries_listed_codes_but_never_aborted() {
+        let retry = Retry::new(&RetryPolicy {
+            retry_on: Some(vec!["timeout".into(), "ABORTED".into()]),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(retry.is_retryable(&actr_error(ErrorCode::Timeout, "slow")));
+        assert!(!retry.is_retryable(&actr_error(ErrorCode::Aborted, "cancelled")));
+        assert!(!retry.is_retryable(&actr_error(ErrorCode::Unavailable, "down")));
+    }
+
+    #[test]
+    fn resolves_the_longest_matching_route_prefix() {
+        let routes = HashMap::from([
+            ("cache.".to_string(), policy(2)),
+            ("cache.Get".to_string(), policy(4)),
+        ]);
+        let policies = RetryPolicies::new(Some(&policy(3)), Some(&routes)).unwrap();
+        let attempts =
+            |route_key, per_call| policies.resolve(route_key, per_call).unwrap().max_attempts;
+        assert_eq!(attempts("cache.GetMany", None), 4);
+        assert_eq!(attempts("cache.Set", None), 2);
+        assert_eq!(attempts("echo.Echo", None), 3);
+        assert_eq!(attempts("cache.Get", Some(&policy(5))), 5);
+        assert_eq!(
+            RetryPolicies::default()
+                .resolve("echo.Echo", None)
+                .unwrap()
+                .max_attempts,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn run_stops_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: napi::Result<()> = run(&Retry::new(&policy(3)).unwrap(), (None, None), || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            async { Err(actr_error(ErrorCode::Unavailable, "down")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn run_does_not_retry_other_errors() {
+        let calls = AtomicU32::new(0);
+        let result: napi::Result<()> = run(&Retry::new(&policy(3)).unwrap(), (None, None), || {
+            calls.fetch_add(1, Ordering::Relaxed);
+            async { Err(actr_error(ErrorCode::Application, "bad request")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn reads_policies_from_the_config_file() {
+        let path = std::env::temp_dir().join(format!("actr-retry-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[retry]\nmax_attempts = 3\nretry_on = [\"TIMEOUT\"]\n\n\
+             [retry.routes.\"cache.\"]\nmax_attempts = 5\ndeadline_ms = 2000\n",
+        )
+        .unwrap();
+        let config = RetryConfig::from_config_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        let config = config.unwrap();
+
+        let default = config.default.unwrap();
+        assert_eq!(default.max_attempts, Some(3));
+        assert_eq!(default.retry_on, Some(vec!["TIMEOUT".to_string()]));
+        let route = &config.routes["cache."];
+        assert_eq!(route.max_attempts, Some(5));
+        assert_eq!(route.deadline_ms, Some(2000));
+    }
+}
@@ -0,0 +1,743 @@
+use actr_framework::Context;
+use actr_runtime::context::RuntimeContext;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+use crate::breaker::{BreakerPermit, CircuitBreakers, CircuitStatus};
+use crate::cache::ResponseCache;
+use crate::coalesce::Coalescer;
+use crate::error::{ErrorCode, actr_error};
+use crate::hashring::HashRing;
+use crate::hedge::{Hedge, Hedging};
+use crate::lease::Leases;
+use crate::retry::{Retry, RetryPolicies, RetryPolicy};
+use crate::tags::Tags;
+use crate::types::{ActrId, LoadBalanceStrategy, SerialNumberMode};
+use crate::workload::{DynamicWorkload, WorkloadOptions};
+
+/// Default lifetime of a discovered candidate list, in milliseconds.
+pub const DEFAULT_TARGET_CACHE_TTL_MS: u32 = 30_000;
+
+/// Default number of candidates requested per discovery.
+pub const DEFAULT_DISCOVERY_COUNT: u32 = 8;
+
+/// First and longest pause between discovery attempts while waiting for candidates.
+const WAIT_BACKOFF_INITIAL: Duration = Duration::from_millis(100);
+const WAIT_BACKOFF_MAX: Duration = Duration::from_secs(2);
+
+/// Timeout of a tag query sent to a candidate, and of all queries of one selection together.
+const TAGS_QUERY_TIMEOUT_MS: i64 = 2_000;
+
+/// Weight of the newest sample in the smoothed latency of a target.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+struct CachedTargets {
+    candidates: Vec<actr_protocol::ActrId>,
+    expires_at: Instant,
+    /// Round-robin position; also the tie-breaking start for the other strategies.
+    next: usize,
+    /// Built on first consistent-hash selection and replaced along with the candidates.
+    ring: Option<Arc<HashRing>>,
+}
+
+/// What this node has observed of one target.
+#[derive(Default)]
+struct TargetStats {
+    outstanding: u32,
+    /// Smoothed response time in ms of successful calls.
+    latency_ms: Option<f64>,
+}
+
+/// How `Router::select` narrows and picks among the candidates of a type.
+#[derive(Default)]
+pub struct Selection<'a> {
+    /// Defaults to the node's strategy.
+    pub strategy: Option<LoadBalanceStrategy>,
+    pub hash_key: Option<&'a str>,
+    /// Only candidates advertising all of these tags.
+    pub tags: Option<&'a Tags>,
+    /// Route about to be called; consulted by per-route circuit breakers.
+    pub route_key: Option<&'a str>,
+}
+
+/// Per-node resolution of `ActrType` destinations into concrete targets.
+///
+/// Candidate lists are cached per type for `ttl` and dropped early when a target fails.
+pub struct Router {
+    ttl: Duration,
+    discovery_count: u32,
+    strategy: LoadBalanceStrategy,
+    virtual_nodes: u32,
+    retry: RetryPolicies,
+    /// Set when `WorkloadOptions.circuitBreaker` is given.
+    breakers: Option<CircuitBreakers>,
+    hedging: Hedging,
+    coalescer: Coalescer,
+    responses: ResponseCache,
+    /// Stream handlers registered on the node.
+    streams: Leases,
+    cache: Mutex<HashMap<String, CachedTargets>>,
+    /// Keyed by canonical ActrId string.
+    stats: Mutex<HashMap<String, TargetStats>>,
+    /// Tags this node advertises on `actr.Tags`.
+    local_tags: Mutex<Tags>,
+    /// Tags advertised by other actors, keyed by canonical ActrId string, cached for `ttl`;
+    /// `None` for actors that are not actr-ts nodes.
+    peer_tags: Mutex<HashMap<String, (Option<Tags>, Instant)>>,
+    /// Node-level context captured in `onStart`; used to query tags outside a handler.
+    context: OnceLock<RuntimeContext>,
+    /// Flipped when the node is shut down through its `ActrRef` or its workload stops.
+    closing: watch::Sender<bool>,
+    /// Set once the node has started; used for multi-candidate discovery.
+    actr_ref: OnceLock<Weak<actr_runtime::ActrRef<DynamicWorkload>>>,
+    /// How ActrIds handed to JS carry their serial number.
+    serial_numbers: SerialNumberMode,
+}
+
+impl Router {
+    pub fn new(options: &WorkloadOptions, serial_numbers: SerialNumberMode) -> napi::Result<Self> {
+        Ok(Self {
+            ttl: Duration::from_millis(
+                options
+                    .target_cache_ttl_ms
+                    .unwrap_or(DEFAULT_TARGET_CACHE_TTL_MS) as u64,
+            ),
+            discovery_count: options
+                .discovery_count
+                .unwrap_or(DEFAULT_DISCOVERY_COUNT)
+                .max(1),
+            strategy: options
+                .load_balance
+                .unwrap_or(LoadBalanceStrategy::RoundRobin),
+            virtual_nodes: options
+                .virtual_nodes
+                .unwrap_or(crate::hashring::DEFAULT_VIRTUAL_NODES),
+            retry: RetryPolicies::new(options.retry.as_ref(), options.retry_routes.as_ref())?,
+            breakers: options
+                .circuit_breaker
+                .as_ref()
+                .map(CircuitBreakers::new)
+                .transpose()?,
+            hedging: Hedging::new(options.hedge_routes.clone()),
+            coalescer: Coalescer::new(options.coalesce_routes.clone()),
+            responses: ResponseCache::new(options.response_cache.as_ref()),
+            streams: Leases::default(),
+            cache: Mutex::new(HashMap::new()),
+            stats: Mutex::new(HashMap::new()),
+            local_tags: Mutex::new(Tags::new()),
+            peer_tags: Mutex::new(HashMap::new()),
+            context: OnceLock::new(),
+            closing: watch::channel(false).0,
+            actr_ref: OnceLock::new(),
+            serial_numbers,
+        })
+    }
+
+    pub fn serial_numbers(&self) -> SerialNumberMode {
+        self.serial_numbers
+    }
+
+    /// `id` as handed to JS by this node.
+    pub fn id_to_js(&self, id: actr_protocol::ActrId) -> ActrId {
+        self.serial_numbers.id_to_js(id)
+    }
+
+    pub fn set_actr_ref(&self, actr_ref: Weak<actr_runtime::ActrRef<DynamicWorkload>>) {
+        let _ = self.actr_ref.set(actr_ref);
+    }
+
+    pub fn set_context(&self, ctx: &RuntimeContext) {
+        let _ = self.context.set(ctx.clone());
+    }
+
+    /// Node-level context, once `onStart` has run.
+    pub fn context(&self) -> Option<&RuntimeContext> {
+        self.context.get()
+    }
+
+    pub fn local_tags(&self) -> Tags {
+        self.local_tags.lock().clone()
+    }
+
+    pub fn set_local_tags(&self, tags: Tags) {
+        *self.local_tags.lock() = tags;
+    }
+
+    /// Retry policy for a call to `route_key`; `per_call` takes precedence over node policies.
+    pub fn retry_policy(
+        &self,
+        route_key: &str,
+        per_call: Option<&RetryPolicy>,
+    ) -> napi::Result<Retry> {
+        self.retry.resolve(route_key, per_call)
+    }
+
+    /// Let a call to `route_key` of `target` through its circuit breaker, if enabled.
+    pub fn admit(
+        &self,
+        target: &actr_protocol::ActrId,
+        route_key: &str,
+    ) -> napi::Result<Option<BreakerPermit<'_>>> {
+        self.breakers
+            .as_ref()
+            .map(|breakers| breakers.acquire(target, route_key))
+            .transpose()
+    }
+
+    /// State of every circuit breaker of the node.
+    pub fn circuit_states(&self) -> Vec<CircuitStatus> {
+        self.breakers
+            .as_ref()
+            .map_or_else(Vec::new, |breakers| breakers.statuses(self.serial_numbers))
+    }
+
+    /// Close the circuits of `target`, or all circuits.
+    pub fn reset_circuits(&self, target: Option<&actr_protocol::ActrId>) {
+        if let Some(breakers) = &self.breakers {
+            breakers.reset(target);
+        }
+    }
+
+    /// How a call to `route_key` is hedged, or `None` when the route is not marked idempotent.
+    pub fn hedge_plan(&self, route_key: &str) -> Option<Hedge> {
+        self.hedging.plan(route_key)
+    }
+
+    /// Record the response time of a successful call to `route_key`.
+    pub fn record_latency(&self, route_key: &str, elapsed: Duration) {
+        self.hedging.record(route_key, elapsed);
+    }
+
+    /// Up to `max` other candidates of `primary`'s type to hedge a call to `route_key` with.
+    ///
+    /// Best effort: a failed discovery leaves the call unhedged.
+    pub async fn hedge_targets(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        primary: &actr_protocol::ActrId,
+        route_key: &str,
+        max: usize,
+    ) -> Vec<actr_protocol::ActrId> {
+        let candidates = match self.candidates(ctx, &primary.r#type).await {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                tracing::debug!("no hedge targets for {route_key}: {e}");
+                return Vec::new();
+            }
+        };
+        candidates
+            .into_iter()
+            .filter(|id| id != primary)
+            .filter(|id| {
+                self.breakers
+                    .as_ref()
+                    .is_none_or(|breakers| breakers.admits(id, Some(route_key)))
+            })
+            .take(max)
+            .collect()
+    }
+
+    /// Identical in-flight calls that opted into coalescing.
+    pub fn coalescer(&self) -> &Coalescer {
+        &self.coalescer
+    }
+
+    /// Responses of cached routes.
+    pub fn response_cache(&self) -> &ResponseCache {
+        &self.responses
+    }
+
+    /// Live stream registrations.
+    pub fn streams(&self) -> &Leases {
+        &self.streams
+    }
+
+    /// Number of candidates requested per discovery.
+    pub fn discovery_count(&self) -> u32 {
+        self.discovery_count
+    }
+
+    /// Signal that the node is shutting down.
+    pub fn close(&self) {
+        self.closing.send_replace(true);
+    }
+
+    /// Resolve once `close` has been called.
+    pub async fn closed(&self) {
+        let _ = self.closing.subscribe().wait_for(|closing| *closing).await;
+    }
+
+    /// Discover up to `count` candidates, bypassing the cache.
+    ///
+    /// Uses the node's `ActrRef` once started; before that (e.g. in `onStart`) falls back to
+    /// the single-candidate discovery of `ctx`.
+    pub async fn discover(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        target_type: &actr_protocol::ActrType,
+        count: u32,
+    ) -> napi::Result<Vec<actr_protocol::ActrId>> {
+        if let Some(actr_ref) = self.actr_ref.get().and_then(Weak::upgrade) {
+            return actr_ref
+                .discover_route_candidates(target_type, count)
+                .await
+                .map_err(crate::error::protocol_error_to_napi);
+        }
+
+        let ctx = ctx.ok_or_else(|| {
+            actr_error(
+                ErrorCode::InvalidState,
+                "node is not running; cannot discover",
+            )
+        })?;
+        let id = Context::discover_route_candidate(ctx, target_type)
+            .await
+            .map_err(crate::error::protocol_error_to_napi)?;
+        Ok(vec![id])
+    }
+
+    /// Re-discover `target_type` until at least `min` candidates matching `filter` are found,
+    /// backing off between attempts, and cache the result. Rejects with `NO_CANDIDATES` once
+    /// `wait` has elapsed, and with `INVALID_STATE` when `min` exceeds what discovery can return
+    /// before the node has started.
+    pub async fn wait_for(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        target_type: &actr_protocol::ActrType,
+        count: u32,
+        min: u32,
+        wait: Duration,
+        filter: Option<&Tags>,
+    ) -> napi::Result<Vec<actr_protocol::ActrId>> {
+        let min = min.max(1);
+        if min > 1 && self.actr_ref.get().is_none() {
+            return Err(actr_error(
+                ErrorCode::InvalidState,
+                format!(
+                    "cannot wait for {min} candidates before the node has started (e.g. in \
+                     onStart): discovery returns a single candidate until then"
+                ),
+            ));
+        }
+        let deadline = Instant::now() + wait;
+        let mut backoff = WAIT_BACKOFF_INITIAL;
+        loop {
+            let found = match self.discover(ctx, target_type, count.max(min)).await {
+                Ok(candidates) => {
+                    self.store(target_type, &candidates);
+                    let candidates = match filter {
+                        Some(filter) => self
+                            .tagged(ctx, candidates, Some(filter))
+                            .await
+                            .into_iter()
+                            .map(|(id, _)| id)
+                            .collect(),
+                        None => candidates,
+                    };
+                    if candidates.len() >= min as usize {
+                        return Ok(candidates);
+                    }
+                    candidates.len()
+                }
+                Err(e)
+                    if is_stale_target_error(&e)
+                        || crate::error::has_code(&e, ErrorCode::Timeout) =>
+                {
+                    0
+                }
+                Err(e) => return Err(e),
+            };
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(actr_error(
+                    ErrorCode::NoCandidates,
+                    format!(
+                        "found {found} of {min} candidates for {} within {}ms",
+                        crate::identity::type_to_string(target_type),
+                        wait.as_millis()
+                    ),
+                ));
+            }
+            tokio::time::sleep(backoff.min(remaining)).await;
+            backoff = (backoff * 2).min(WAIT_BACKOFF_MAX);
+        }
+    }
+
+    /// Cached candidates for `target_type`, discovering them if missing or expired.
+    pub async fn candidates(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        target_type: &actr_protocol::ActrType,
+    ) -> napi::Result<Vec<actr_protocol::ActrId>> {
+        let key = crate::identity::type_to_string(target_type);
+        if let Some(cached) = self.cache.lock().get(&key)
+            && cached.expires_at > Instant::now()
+            && !cached.candidates.is_empty()
+        {
+            return Ok(cached.candidates.clone());
+        }
+
+        let candidates = self
+            .discover(ctx, target_type, self.discovery_count)
+            .await?;
+        if candidates.is_empty() {
+            return Err(actr_error(
+                ErrorCode::NoCandidates,
+                format!("no candidates for {key}"),
+            ));
+        }
+        self.store(target_type, &candidates);
+        Ok(candidates)
+    }
+
+    /// Replace the cached candidates of `target_type` with a fresh discovery result.
+    pub fn store(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        candidates: &[actr_protocol::ActrId],
+    ) {
+        if candidates.is_empty() {
+            return;
+        }
+        let mut cache = self.cache.lock();
+        let next = cache
+            .get(&crate::identity::type_to_string(target_type))
+            .map_or(0, |cached| cached.next);
+        cache.insert(
+            crate::identity::type_to_string(target_type),
+            CachedTargets {
+                candidates: candidates.to_vec(),
+                expires_at: Instant::now() + self.ttl,
+                next,
+                ring: None,
+            },
+        );
+    }
+
+    /// Tags advertised by `id`, queried on `actr.Tags` and cached for the candidate TTL.
+    ///
+    /// Actors that do not answer the query report no tags.
+    pub async fn tags_of(&self, ctx: Option<&RuntimeContext>, id: &actr_protocol::ActrId) -> Tags {
+        match self.query_tags(ctx, id, TAGS_QUERY_TIMEOUT_MS).await {
+            Ok(tags) => tags.unwrap_or_default(),
+            Err(e) => {
+                tracing::debug!(
+                    "failed to query tags of {}: {e}",
+                    crate::identity::id_to_string(id)
+                );
+                Tags::default()
+            }
+        }
+    }
+
+    /// Whether `id` is an actr-ts node, which strips metadata frames from payloads, asking it
+    /// within `timeout_ms` (at most the tags query timeout) unless the answer is cached.
+    ///
+    /// Fails with the query's own error when `id` could not be asked.
+    pub async fn is_actr_ts(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        id: &actr_protocol::ActrId,
+        timeout_ms: i64,
+    ) -> napi::Result<bool> {
+        let timeout_ms = timeout_ms.clamp(1, TAGS_QUERY_TIMEOUT_MS);
+        Ok(self.query_tags(ctx, id, timeout_ms).await?.is_some())
+    }
+
+    /// Whether `id` is an actr-ts node according to a cached answer; never queries it.
+    pub fn known_actr_ts(&self, id: &actr_protocol::ActrId) -> bool {
+        self.cached_tags(&crate::identity::id_to_string(id))
+            .is_some_and(|tags| tags.is_some())
+    }
+
+    /// Unexpired cached answer to a tags query of the actor keyed `key`.
+    fn cached_tags(&self, key: &str) -> Option<Option<Tags>> {
+        self.peer_tags
+            .lock()
+            .get(key)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(tags, _)| tags.clone())
+    }
+
+    /// Tags of `id`, or `None` when it is not an actr-ts node.
+    ///
+    /// Answers are cached for the candidate TTL; failures to ask `id` are returned uncached.
+    async fn query_tags(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        id: &actr_protocol::ActrId,
+        timeout_ms: i64,
+    ) -> napi::Result<Option<Tags>> {
+        let key = crate::identity::id_to_string(id);
+        if let Some(tags) = self.cached_tags(&key) {
+            return Ok(tags);
+        }
+        let ctx = ctx.or_else(|| self.context.get()).ok_or_else(|| {
+            actr_error(
+                ErrorCode::InvalidState,
+                format!("cannot query tags of {key} before the node has started"),
+            )
+        })?;
+
+        let response = ctx
+            .call_raw(
+                &actr_framework::Dest::Actor(id.clone()),
+                crate::tags::TAGS_ROUTE_KEY.to_string(),
+                actr_protocol::PayloadType::RpcReliable,
+                Bytes::new(),
+                timeout_ms,
+            )
+            .await
+            .map_err(crate::error::protocol_error_to_napi);
+        let tags = match response.and_then(crate::tags::decode) {
+            Ok(tags) => Some(tags),
+            // Not an actr-ts node: it has no tags and asking again will not change that.
+            Err(e) if crate::error::has_code(&e, ErrorCode::UnknownRoute) => None,
+            Err(e) => return Err(e),
+        };
+        self.peer_tags
+            .lock()
+            .insert(key, (tags.clone(), Instant::now() + self.ttl));
+        Ok(tags)
+    }
+
+    /// Pair each of `ids` with its tags, keeping those that match `filter`.
+    ///
+    /// Candidates are queried concurrently; those that have not answered once the query timeout
+    /// has elapsed report no tags.
+    pub async fn tagged(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        ids: Vec<actr_protocol::ActrId>,
+        filter: Option<&Tags>,
+    ) -> Vec<(actr_protocol::ActrId, Tags)> {
+        let mut tags = vec![None; ids.len()];
+        let mut pending: FuturesUnordered<_> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| async move { (i, self.tags_of(ctx, id).await) })
+            .collect();
+        let deadline = tokio::time::sleep(Duration::from_millis(TAGS_QUERY_TIMEOUT_MS as u64));
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                answered = pending.next() => match answered {
+                    Some((i, answer)) => tags[i] = Some(answer),
+                    None => break,
+                },
+                _ = &mut deadline => break,
+            }
+        }
+        drop(pending);
+
+        ids.into_iter()
+            .zip(tags)
+            .map(|(id, tags)| (id, tags.unwrap_or_default()))
+            .filter(|(_, tags)| filter.is_none_or(|filter| crate::tags::matches(tags, filter)))
+            .collect()
+    }
+
+    /// Pick one candidate of `target_type` as described by `selection`.
+    pub async fn select(
+        &self,
+        ctx: Option<&RuntimeContext>,
+        target_type: &actr_protocol::ActrType,
+        selection: &Selection<'_>,
+    ) -> napi::Result<actr_protocol::ActrId> {
+        let strategy = selection.strategy.unwrap_or(self.strategy);
+        let hash_key = selection.hash_key;
+        if matches!(strategy, LoadBalanceStrategy::ConsistentHash) && hash_key.is_none() {
+            return Err(actr_error(
+                ErrorCode::InvalidArgument,
+                "hashKey is required by LoadBalanceStrategy.ConsistentHash",
+            ));
+        }
+
+        let mut candidates = self.candidates(ctx, target_type).await?;
+        let filter = selection.tags.filter(|tags| !tags.is_empty());
+        if let Some(filter) = filter {
+            candidates = self
+                .tagged(ctx, candidates, Some(filter))
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            if candidates.is_empty() {
+                return Err(actr_error(
+                    ErrorCode::NoCandidates,
+                    format!(
+                        "no candidates for {} match the requested tags",
+                        crate::identity::type_to_string(target_type)
+                    ),
+                ));
+            }
+        }
+        let mut skipped = false;
+        if let Some(breakers) = &self.breakers {
+            let total = candidates.len();
+            candidates.retain(|id| breakers.admits(id, selection.route_key));
+            if candidates.is_empty() {
+                return Err(actr_error(
+                    ErrorCode::CircuitOpen,
+                    format!(
+                        "circuits of all {total} candidates for {} are open",
+                        crate::identity::type_to_string(target_type)
+                    ),
+                ));
+            }
+            skipped = candidates.len() < total;
+        }
+        let start = self
+            .cache
+            .lock()
+            .get_mut(&crate::identity::type_to_string(target_type))
+            .map(|cached| {
+                cached.next = cached.next.wrapping_add(1);
+                cached.next - 1
+            })
+            .unwrap_or(0);
+
+        let index = match strategy {
+            LoadBalanceStrategy::RoundRobin => start % candidates.len(),
+            LoadBalanceStrategy::Random => fastrand::usize(..candidates.len()),
+            LoadBalanceStrategy::LeastOutstanding => {
+                let stats = self.stats.lock();
+                min_from(start, &candidates, |id| {
+                    stats
+                        .get(&crate::identity::id_to_string(id))
+                        .map_or(0, |s| s.outstanding) as f64
+                })
+            }
+            LoadBalanceStrategy::LowestLatency => {
+                let stats = self.stats.lock();
+                min_from(start, &candidates, |id| {
+                    stats
+                        .get(&crate::identity::id_to_string(id))
+                        .and_then(|s| s.latency_ms)
+                        .unwrap_or(0.0)
+                })
+            }
+            LoadBalanceStrategy::ConsistentHash => {
+                // Keys owned by a filtered-out candidate move to the next one on a ring of
+                // the remaining candidates.
+                let ring = if filter.is_some() || skipped {
+                    Arc::new(HashRing::new(&candidates, self.virtual_nodes))
+                } else {
+                    self.ring(target_type, &candidates)
+                };
+                if let Some(id) = ring.get(hash_key.unwrap_or_default()) {
+                    return Ok(id.clone());
+                }
+                0
+            }
+        };
+        Ok(candidates[index].clone())
+    }
+
+    /// Hash ring over the cached candidates of `target_type`, rebuilt whenever they change.
+    fn ring(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        candidates: &[actr_protocol::ActrId],
+    ) -> Arc<HashRing> {
+        match self
+            .cache
+            .lock()
+            .get_mut(&crate::identity::type_to_string(target_type))
+        {
+            Some(cached) => cached
+                .ring
+                .get_or_insert_with(|| {
+                    Arc::new(HashRing::new(&cached.candidates, self.virtual_nodes))
+                })
+                .clone(),
+            None => Arc::new(HashRing::new(candidates, self.virtual_nodes)),
+        }
+    }
+
+    /// Count a call to `target` as outstanding until the returned guard is dropped.
+    pub fn track(&self, target: &actr_protocol::ActrId) -> InFlight<'_> {
+        let key = crate::identity::id_to_string(target);
+        self.stats
+            .lock()
+            .entry(key.clone())
+            .or_default()
+            .outstanding += 1;
+        InFlight {
+            router: self,
+            key,
+            started: Instant::now(),
+        }
+    }
+
+    /// Forget every cached candidate of `target_type`.
+    pub fn invalidate(&self, target_type: &actr_protocol::ActrType) {
+        let type_key = crate::identity::type_to_string(target_type);
+        self.cache.lock().remove(&type_key);
+        let suffix = format!("/{type_key}");
+        self.stats
+            .lock()
+            .retain(|id, stats| stats.outstanding > 0 || !id.ends_with(&suffix));
+    }
+}
+
+/// An outstanding call tracked by `Router::track`.
+pub struct InFlight<'a> {
+    router: &'a Router,
+    key: String,
+    started: Instant,
+}
+
+impl InFlight<'_> {
+    /// Record the response time of a successful call to `route_key`.
+    pub fn succeeded(self, route_key: &str) {
+        self.router
+            .record_latency(route_key, self.started.elapsed());
+        let elapsed = self.started.elapsed().as_secs_f64() * 1000.0;
+        if let Some(stats) = self.router.stats.lock().get_mut(&self.key) {
+            stats.latency_ms = Some(match stats.latency_ms {
+                Some(latency) => latency + LATENCY_SMOOTHING * (elapsed - latency),
+                None => elapsed,
+            });
+        }
+    }
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        if let Some(stats) = self.router.stats.lock().get_mut(&self.key) {
+            stats.outstanding = stats.outstanding.saturating_sub(1);
+        }
+    }
+}
+
+/// Index of the candidate with the lowest `cost`, scanning from `start` so ties rotate.
+fn min_from(
+    start: usize,
+    candidates: &[actr_protocol::ActrId],
+    cost: impl Fn(&actr_protocol::ActrId) -> f64,
+) -> usize {
+    (0..candidates.len())
+        .map(|offset| (start + offset) % candidates.len())
+        .min_by(|&a, &b| cost(&candidates[a]).total_cmp(&cost(&candidates[b])))
+        .unwrap_or(0)
+}
+
+/// Whether a failed call most likely never reached a live target, so re-discovering and trying
+/// another candidate is safe.
+pub fn is_stale_target_error(e: &napi::Error) -> bool {
+    [
+        ErrorCode::NotFound,
+        ErrorCode::Unavailable,
+        ErrorCode::Transport,
+    ]
+    .into_iter()
+    .any(|code| crate::error::has_code(e, code))
+}
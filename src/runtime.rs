@@ -1,23 +1,58 @@
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::types::{ActrId, ActrType, PayloadType};
-use crate::workload::DynamicWorkload;
+use crate::breaker::CircuitStatus;
+use crate::cache::{CacheKey, CacheStats};
+use crate::coalesce::CallKey;
+use crate::context::ContextBridge;
+use crate::error::ErrorCode;
+use crate::retry::RetryConfig;
+use crate::routing::{Router, Selection};
+use crate::types::{
+    ActrId, ActrType, CallOptions, DiscoverOptions, DiscoveredActor, LoadBalanceStrategy,
+    PayloadType, SerialNumberMode,
+};
+use crate::watch::{DiscoveryWatch, WatchCallback, WatchOptions};
+use crate::workload::{DynamicWorkload, WorkloadOptions};
+
+/// Options applied when creating an ActrSystem.
+#[napi(object)]
+#[derive(Default)]
+pub struct SystemOptions {
+    /// How `ActrId.serialNumber` is handed to JS by this system (default `BigInt`).
+    #[napi(js_name = "serialNumberMode")]
+    pub serial_number_mode: Option<SerialNumberMode>,
+}
 
 #[napi]
 pub struct ActrSystem {
     inner: Option<actr_runtime::ActrSystem>,
     #[allow(dead_code)]
     config: actr_config::Config,
+    /// Initial tags from the `[tags]` table of the config file.
+    tags: crate::tags::Tags,
+    /// Retry policies from the `[retry]` table of the config file.
+    retry: Option<RetryConfig>,
+    serial_numbers: SerialNumberMode,
 }
 
 #[napi]
 impl ActrSystem {
     /// Create ActrSystem from a config file path.
+    ///
+    /// The optional `[tags]` table of the file becomes the node's advertised tags, and the
+    /// optional `[retry]` table supplies retry policies the attach options leave unset.
     #[napi(factory)]
-    pub async fn from_file(config_path: String) -> Result<ActrSystem> {
+    pub async fn from_file(
+        config_path: String,
+        options: Option<SystemOptions>,
+    ) -> Result<ActrSystem> {
         let config = actr_config::ConfigParser::from_file(&config_path)
             .map_err(crate::error::config_error_to_napi)?;
+        let tags = crate::tags::from_config_file(&config_path)?;
+        let retry = RetryConfig::from_config_file(&config_path)?;
 
         crate::logger::init_observability(config.observability.clone());
 
@@ -28,56 +63,136 @@ impl ActrSystem {
         Ok(ActrSystem {
             inner: Some(system),
             config,
+            tags,
+            retry: Some(retry),
+            serial_numbers: options
+                .unwrap_or_default()
+                .serial_number_mode
+                .unwrap_or(SerialNumberMode::BigInt),
         })
     }
 
     /// Attach a workload and create ActrNode.
     #[napi]
-    pub fn attach(&mut self, callback: Object) -> Result<ActrNode> {
-        let system = self
-            .inner
-            .take()
-            .ok_or_else(|| Error::from_reason("System already consumed"))?;
+    pub fn attach(
+        &mut self,
+        callback: Object,
+        options: Option<WorkloadOptions>,
+    ) -> Result<ActrNode> {
+        let system = self.inner.take().ok_or_else(|| {
+            crate::error::actr_error(ErrorCode::InvalidState, "System already consumed")
+        })?;
 
-        let workload = DynamicWorkload::new(callback)?;
+        let mut options = options.unwrap_or_default();
+        if let Some(retry) = self.retry.take() {
+            retry.apply(&mut options.retry, &mut options.retry_routes);
+        }
+        let router = Arc::new(Router::new(&options, self.serial_numbers)?);
+        router.set_local_tags(std::mem::take(&mut self.tags));
+        let workload = DynamicWorkload::new(callback, options, router.clone())?;
         let node = system.attach(workload);
 
-        Ok(ActrNode { inner: Some(node) })
+        Ok(ActrNode {
+            inner: Some(node),
+            router,
+        })
     }
 }
 
 #[napi]
 pub struct ActrNode {
     inner: Option<actr_runtime::ActrNode<DynamicWorkload>>,
+    router: Arc<Router>,
 }
 
 #[napi]
 impl ActrNode {
     /// Start the node and return ActrRef.
     ///
+    /// Rejects if the workload's `onStart` rejects or exceeds its start timeout.
+    ///
     /// # Safety
     ///
     /// This function is unsafe because it takes ownership of the internal node and
     /// starts the actor runtime. It must only be called once.
     #[napi]
     pub async unsafe fn start(&mut self) -> Result<ActrRef> {
-        let node = self
-            .inner
-            .take()
-            .ok_or_else(|| Error::from_reason("Node already started"))?;
+        let node = self.inner.take().ok_or_else(|| {
+            crate::error::actr_error(ErrorCode::InvalidState, "Node already started")
+        })?;
 
         let actr_ref = node
             .start()
             .await
             .map_err(crate::error::protocol_error_to_napi)?;
+        let actr_ref = Arc::new(actr_ref);
+        self.router.set_actr_ref(Arc::downgrade(&actr_ref));
 
-        Ok(ActrRef { inner: actr_ref })
+        Ok(ActrRef {
+            inner: actr_ref,
+            router: self.router.clone(),
+        })
     }
 }
 
 #[napi]
 pub struct ActrRef {
-    inner: actr_runtime::ActrRef<DynamicWorkload>,
+    inner: Arc<actr_runtime::ActrRef<DynamicWorkload>>,
+    router: Arc<Router>,
+}
+
+impl ActrRef {
+    /// Context bridge for calls to other actors, sharing the node's target cache.
+    fn bridge(&self) -> Result<ContextBridge> {
+        let ctx = self.router.context().ok_or_else(|| {
+            crate::error::actr_error(
+                ErrorCode::InvalidState,
+                "node context is not available before onStart has run",
+            )
+        })?;
+        Ok(ContextBridge::from_runtime(
+            ctx.clone(),
+            self.router.clone(),
+        ))
+    }
+
+    /// Discover actors of `target_type` as described by `options`, applying its tag filter.
+    async fn discover_ids(
+        &self,
+        target_type: &actr_protocol::ActrType,
+        count: u32,
+        options: &DiscoverOptions,
+    ) -> Result<Vec<actr_protocol::ActrId>> {
+        if options.waits() {
+            return self
+                .router
+                .wait_for(
+                    None,
+                    target_type,
+                    count,
+                    options.min_candidates.unwrap_or(1),
+                    std::time::Duration::from_millis(options.wait_ms.unwrap_or(0) as u64),
+                    options.tags.as_ref(),
+                )
+                .await;
+        }
+
+        let ids = self
+            .inner
+            .discover_route_candidates(target_type, count)
+            .await
+            .map_err(crate::error::protocol_error_to_napi)?;
+        Ok(match &options.tags {
+            Some(filter) => self
+                .router
+                .tagged(None, ids, Some(filter))
+                .await
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect(),
+            None => ids,
+        })
+    }
 }
 
 #[napi]
@@ -85,20 +200,163 @@ impl ActrRef {
     /// Get the actor ID.
     #[napi]
     pub fn actor_id(&self) -> ActrId {
-        self.inner.actor_id().clone().into()
+        self.router.id_to_js(self.inner.actor_id().clone())
     }
 
     /// Discover actors of the given type.
+    ///
+    /// With `options.waitMs`, keeps re-discovering with backoff until at least
+    /// `options.minCandidates` are found, rejecting with `NO_CANDIDATES` when time runs out.
     #[napi]
-    pub async fn discover(&self, target_type: ActrType, count: u32) -> Result<Vec<ActrId>> {
-        let proto_type: actr_protocol::ActrType = target_type.into();
+    pub async fn discover(
+        &self,
+        target_type: ActrType,
+        count: u32,
+        options: Option<DiscoverOptions>,
+    ) -> Result<Vec<ActrId>> {
+        let options = options.unwrap_or_default();
         let ids = self
-            .inner
-            .discover_route_candidates(&proto_type, count)
+            .discover_ids(&target_type.into(), count, &options)
+            .await?;
+
+        Ok(ids.into_iter().map(|id| self.router.id_to_js(id)).collect())
+    }
+
+    /// Discover actors of the given type together with the tags they advertise.
+    ///
+    /// Accepts the same options as `discover`.
+    #[napi]
+    pub async fn discover_with_tags(
+        &self,
+        target_type: ActrType,
+        count: u32,
+        options: Option<DiscoverOptions>,
+    ) -> Result<Vec<DiscoveredActor>> {
+        let options = options.unwrap_or_default();
+        let ids = self
+            .discover_ids(&target_type.into(), count, &options)
+            .await?;
+
+        Ok(self
+            .router
+            .tagged(None, ids, None)
             .await
-            .map_err(crate::error::protocol_error_to_napi)?;
+            .into_iter()
+            .map(|(id, tags)| DiscoveredActor {
+                id: self.router.id_to_js(id),
+                tags,
+            })
+            .collect())
+    }
+
+    /// Replace the tags this node advertises to discovering actors.
+    ///
+    /// Initial tags come from the `[tags]` table of `Actr.toml`. Peers see changes once their
+    /// cached copy expires.
+    #[napi]
+    pub fn set_tags(&self, tags: HashMap<String, String>) {
+        self.router.set_local_tags(tags);
+    }
+
+    /// Tags this node advertises.
+    #[napi]
+    pub fn tags(&self) -> HashMap<String, String> {
+        self.router.local_tags()
+    }
+
+    /// Resolve an actor of the given type from the node's cached candidates.
+    ///
+    /// Shares the cache and load-balancing state used by `ctx.callRawByType`; discovery runs
+    /// only when the cache for the type is empty or expired.
+    #[napi]
+    pub async fn resolve(
+        &self,
+        target_type: ActrType,
+        options: Option<DiscoverOptions>,
+    ) -> Result<ActrId> {
+        let proto_type: actr_protocol::ActrType = target_type.into();
+        let options = options.unwrap_or_default();
+        let id = self
+            .router
+            .select(None, &proto_type, &options.selection())
+            .await?;
+        Ok(self.router.id_to_js(id))
+    }
+
+    /// Resolve the actor of the given type that owns `shard_key` on the node's hash ring.
+    ///
+    /// Matches the target picked by `ctx.callRawByKey` for the same key.
+    #[napi]
+    pub async fn resolve_by_key(&self, target_type: ActrType, shard_key: String) -> Result<ActrId> {
+        let proto_type: actr_protocol::ActrType = target_type.into();
+        let id = self
+            .router
+            .select(
+                None,
+                &proto_type,
+                &Selection {
+                    strategy: Some(LoadBalanceStrategy::ConsistentHash),
+                    hash_key: Some(&shard_key),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(self.router.id_to_js(id))
+    }
+
+    /// Watch actors of the given type joining and leaving.
+    ///
+    /// The first discovery round reports every current actor as `joined`. Each round also
+    /// refreshes the candidates used when calling by type.
+    #[napi]
+    pub async fn watch(
+        &self,
+        target_type: ActrType,
+        #[napi(ts_arg_type = "(err: Error | null, event: DiscoveryEvent) => void")]
+        callback: WatchCallback,
+        options: Option<WatchOptions>,
+    ) -> DiscoveryWatch {
+        crate::watch::spawn(
+            self.router.clone(),
+            Arc::downgrade(&self.inner),
+            target_type.into(),
+            callback,
+            options.unwrap_or_default(),
+        )
+    }
+
+    /// Drop the cached candidates of the given type so the next call re-discovers.
+    #[napi]
+    pub fn invalidate_targets(&self, target_type: ActrType) {
+        self.router.invalidate(&target_type.into());
+    }
+
+    /// State of every circuit breaker of the node; empty unless `circuitBreaker` is configured.
+    #[napi]
+    pub fn circuit_states(&self) -> Vec<CircuitStatus> {
+        self.router.circuit_states()
+    }
+
+    /// Close the circuits of `target`, or every circuit when omitted.
+    #[napi]
+    pub fn reset_circuits(&self, target: Option<ActrId>) -> Result<()> {
+        let target: Option<actr_protocol::ActrId> = target.map(TryInto::try_into).transpose()?;
+        self.router.reset_circuits(target.as_ref());
+        Ok(())
+    }
 
-        Ok(ids.into_iter().map(|id| id.into()).collect())
+    /// Drop cached responses of routes starting with `route_prefix`, or every cached response.
+    #[napi]
+    pub fn invalidate_cache(&self, route_prefix: Option<String>) {
+        self.router
+            .response_cache()
+            .invalidate(route_prefix.as_deref());
+    }
+
+    /// Hit, miss and eviction counters and current size of the response cache.
+    #[napi]
+    pub fn cache_stats(&self) -> CacheStats {
+        self.router.response_cache().stats()
     }
 
     /// Call remote actor (RPC).
@@ -109,46 +367,199 @@ impl ActrRef {
         payload_type: PayloadType,
         request_payload: Buffer,
         timeout_ms: i64,
+        options: Option<CallOptions>,
     ) -> Result<Buffer> {
-        let proto_payload_type: actr_protocol::PayloadType = payload_type.into();
-        let response = self
-            .inner
-            .call_raw(
+        let options = options.unwrap_or_default();
+        let payload = crate::headers::encode(
+            &options.metadata_entries()?,
+            bytes::Bytes::from(request_payload.to_vec()),
+        );
+        let proto_payload_type: actr_protocol::PayloadType =
+            options.payload_type.unwrap_or(payload_type).into();
+        let timeout_ms = options.timeout_ms.unwrap_or(timeout_ms);
+        let cancel = crate::cancel::lookup(options.cancel_token_id)?;
+        let retry = self
+            .router
+            .retry_policy(&route_key, options.retry.as_ref())?;
+
+        let (route_key, cancel) = (&route_key, &cancel);
+        let send = || {
+            crate::retry::run(&retry, (cancel.clone(), None), || {
+                let (route_key, payload) = (route_key.clone(), payload.clone());
+                crate::cancel::with_cancel(cancel.clone(), async move {
+                    self.inner
+                        .call_raw(route_key, payload, timeout_ms, proto_payload_type)
+                        .await
+                        .map_err(crate::error::protocol_error_to_napi)
+                })
+            })
+        };
+        let dest = crate::identity::id_to_string(self.inner.actor_id());
+        let fetch = || async {
+            if !self.router.coalescer().applies(route_key, options.coalesce) {
+                return send().await;
+            }
+            let key = CallKey {
+                dest: dest.clone(),
+                route_key: route_key.clone(),
+                payload_type: proto_payload_type as i32,
+                metadata: Vec::new(),
+                payload: payload.clone(),
+            };
+            self.router
+                .coalescer()
+                .run(key, (cancel.clone(), None), send)
+                .await
+        };
+        let response = match self.router.response_cache().ttl(route_key, options.cache) {
+            Some(ttl) => {
+                let key = CacheKey::new(
+                    dest.clone(),
+                    route_key,
+                    proto_payload_type as i32,
+                    &[],
+                    &payload,
+                );
+                self.router
+                    .response_cache()
+                    .get_or_fetch(key, ttl, fetch)
+                    .await?
+            }
+            None => fetch().await?,
+        };
+
+        Ok(response.to_vec().into())
+    }
+
+    /// Call an actor of the given type.
+    ///
+    /// Uses the same cached candidates and load balancing as `ctx.callRawByType`: if the chosen
+    /// target turns out to be gone, the cache is dropped and the call is retried once on a
+    /// freshly discovered target.
+    #[napi]
+    pub async fn call_by_type(
+        &self,
+        target_type: ActrType,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        timeout_ms: i64,
+        options: Option<CallOptions>,
+    ) -> Result<Buffer> {
+        self.bridge()?
+            .call_raw_by_type(
+                target_type,
                 route_key,
-                bytes::Bytes::from(request_payload.to_vec()),
+                payload_type,
+                payload,
                 timeout_ms,
-                proto_payload_type,
+                options,
             )
             .await
-            .map_err(crate::error::protocol_error_to_napi)?;
+    }
 
-        Ok(response.to_vec().into())
+    /// Send one-way message to an actor of the given type, using the cached candidates.
+    #[napi]
+    pub async fn tell_by_type(
+        &self,
+        target_type: ActrType,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        self.bridge()?
+            .tell_raw_by_type(target_type, route_key, payload_type, payload, options)
+            .await
     }
 
-    /// Send one-way message (fire-and-forget).
+    /// Call the actor of the given type that owns `shard_key` on the node's hash ring.
+    ///
+    /// Picks the same target as `ctx.callRawByKey` and `resolveByKey` for the same key.
     #[napi]
-    pub async fn tell(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn call_by_key(
         &self,
+        target_type: ActrType,
+        shard_key: String,
         route_key: String,
         payload_type: PayloadType,
-        message_payload: Buffer,
+        payload: Buffer,
+        timeout_ms: i64,
+        options: Option<CallOptions>,
+    ) -> Result<Buffer> {
+        self.bridge()?
+            .call_raw_by_key(
+                target_type,
+                shard_key,
+                route_key,
+                payload_type,
+                payload,
+                timeout_ms,
+                options,
+            )
+            .await
+    }
+
+    /// Send one-way message to the actor of the given type that owns `shard_key`.
+    #[napi]
+    pub async fn tell_by_key(
+        &self,
+        target_type: ActrType,
+        shard_key: String,
+        route_key: String,
+        payload_type: PayloadType,
+        payload: Buffer,
+        options: Option<CallOptions>,
     ) -> Result<()> {
-        let proto_payload_type: actr_protocol::PayloadType = payload_type.into();
-        self.inner
-            .tell_raw(
+        self.bridge()?
+            .tell_raw_by_key(
+                target_type,
+                shard_key,
                 route_key,
-                bytes::Bytes::from(message_payload.to_vec()),
-                proto_payload_type,
+                payload_type,
+                payload,
+                options,
             )
             .await
-            .map_err(crate::error::protocol_error_to_napi)?;
+    }
 
-        Ok(())
+    /// Send one-way message (fire-and-forget).
+    #[napi]
+    pub async fn tell(
+        &self,
+        route_key: String,
+        payload_type: PayloadType,
+        message_payload: Buffer,
+        options: Option<CallOptions>,
+    ) -> Result<()> {
+        let options = options.unwrap_or_default();
+        let payload = crate::headers::encode(
+            &options.metadata_entries()?,
+            bytes::Bytes::from(message_payload.to_vec()),
+        );
+        let proto_payload_type: actr_protocol::PayloadType =
+            options.payload_type.unwrap_or(payload_type).into();
+        let cancel = crate::cancel::lookup(options.cancel_token_id)?;
+        let retry = self
+            .router
+            .retry_policy(&route_key, options.retry.as_ref())?;
+        crate::retry::run(&retry, (cancel.clone(), None), || {
+            let (route_key, payload) = (route_key.clone(), payload.clone());
+            crate::cancel::with_cancel(cancel.clone(), async move {
+                self.inner
+                    .tell_raw(route_key, payload, proto_payload_type)
+                    .await
+                    .map_err(crate::error::protocol_error_to_napi)
+            })
+        })
+        .await
     }
 
-    /// Trigger shutdown.
+    /// Trigger shutdown. Stops every discovery watch of the node.
     #[napi]
     pub fn shutdown(&self) {
+        self.router.close();
         self.inner.shutdown();
     }
 
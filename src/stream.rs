@@ -0,0 +1,886 @@
+// Buffered stream receivers
+//
+// `ctx.receiveStream` registers a handler for a stream ID that queues incoming chunks in a bounded
+// buffer, read one at a time with `next()` (`for await` in TypeScript). When the buffer holds
+// `highWaterMark` chunks, `overflow` decides what happens to the next one: `Block` keeps the
+// runtime's handler waiting until the consumer catches up, which pushes back on the sender, while
+// the other policies drop a chunk or fail the receiver, and count it as an overflow.
+//
+// With `reorderWindow`, chunks are delivered in sequence order starting at `startSequence`: a
+// chunk that arrives ahead of a missing sequence number is held until the gap fills, and the gap
+// is skipped (and reported) once more than the window is held or, with `jitterMs`, once a chunk
+// has been held that long. Control chunks take their place in the sequence too, so an `end()`
+// overtaking data is not early.
+//
+// `ctx.openStream` returns a writer that numbers and timestamps chunks itself. Its `end()` and
+// `abort(reason)` send a control chunk marked with the reserved `actr-stream-control` metadata
+// key, so receivers can tell a finished stream from a failed one: a receiver ends after reading
+// the chunks before `end()`, and fails with `STREAM_ABORTED` after those before `abort()`.
+//
+// The handler is unregistered once the stream is over (see `lease`); a receiver closed by an idle
+// timeout, a departed sender or the workload stopping still yields the chunks it holds first.
+
+use actr_framework::Context;
+use actr_runtime::context::RuntimeContext;
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Notify;
+
+use crate::error::{ErrorCode, actr_error};
+use crate::lease::{Lease, Lifetime, StreamCloseReason};
+use crate::routing::Router;
+use crate::types::{ActrId, SerialNumberMode, StreamSignal};
+
+/// Default number of chunks a receiver buffers.
+const DEFAULT_HIGH_WATER_MARK: u32 = 64;
+
+/// Reserved metadata key of a control chunk; the value is `end` or `abort`, and the payload of
+/// an `abort` chunk is the reason.
+pub const STREAM_CONTROL_KEY: &str = "actr-stream-control";
+
+/// End-of-stream signal sent by a `StreamWriter`.
+pub enum Control {
+    End,
+    Abort(String),
+}
+
+impl Control {
+    /// Control signal carried by `chunk`, if it is a control chunk.
+    pub fn of(chunk: &actr_protocol::DataStream) -> Option<Self> {
+        let entry = chunk
+            .metadata
+            .iter()
+            .find(|entry| entry.key == STREAM_CONTROL_KEY)?;
+        match entry.value.as_str() {
+            "end" => Some(Control::End),
+            "abort" => Some(Control::Abort(
+                String::from_utf8_lossy(&chunk.payload).into_owned(),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Error a receiver of `stream_id` fails with after an `abort`.
+    pub fn aborted(stream_id: &str, reason: &str) -> napi::Error {
+        actr_error(
+            ErrorCode::StreamAborted,
+            format!("stream {stream_id} aborted by sender: {reason}"),
+        )
+    }
+}
+
+/// What a receiver does with a chunk that arrives while its buffer is full.
+#[napi]
+#[derive(PartialEq, Eq)]
+pub enum StreamOverflow {
+    /// Hold the chunk until the consumer frees a slot, pausing delivery from the runtime.
+    Block,
+    /// Drop the oldest buffered chunk to make room.
+    DropOldest,
+    /// Drop the incoming chunk.
+    DropNewest,
+    /// Fail the receiver with `STREAM_OVERFLOW` once the buffered chunks are read.
+    Error,
+}
+
+/// Options for `ctx.receiveStream`.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct StreamReceiverOptions {
+    /// Chunks buffered before `overflow` applies (default 64).
+    #[napi(js_name = "highWaterMark")]
+    pub high_water_mark: Option<u32>,
+    /// What to do when the buffer is full (default `Block`).
+    pub overflow: Option<StreamOverflow>,
+    /// Chunks held back to restore sequence order; missing sequence numbers are skipped once
+    /// more are waiting (default 0: deliver in arrival order).
+    #[napi(js_name = "reorderWindow")]
+    pub reorder_window: Option<u32>,
+    /// With a reorder window, skip missing sequence numbers once a held chunk has waited this
+    /// long, in ms. Meant for `StreamLatencyFirst` streams, where lost chunks are not resent.
+    /// Requires `reorderWindow`.
+    #[napi(js_name = "jitterMs")]
+    pub jitter_ms: Option<u32>,
+    /// With a reorder window, sequence number of the sender's first chunk (default 0, as for
+    /// `ctx.openStream`).
+    #[napi(js_name = "startSequence")]
+    pub start_sequence: Option<i64>,
+    /// Close the receiver when no chunk arrives for this long, in ms (default: never).
+    #[napi(js_name = "idleTimeoutMs")]
+    pub idle_timeout_ms: Option<u32>,
+    /// How often the sender is checked to still be registered, in ms (default: never).
+    #[napi(js_name = "peerCheckMs")]
+    pub peer_check_ms: Option<u32>,
+}
+
+/// Counters of a stream receiver, as reported by `StreamReceiver.stats`.
+#[napi(object)]
+pub struct StreamStats {
+    /// Data chunks received from the runtime.
+    pub received: i64,
+    /// Chunks read with `next()`.
+    pub delivered: i64,
+    /// Chunks dropped because their sequence number was already delivered or skipped.
+    pub duplicates: i64,
+    /// Chunks that arrived ahead of a missing sequence number.
+    pub reordered: i64,
+    /// Times missing sequence numbers were skipped.
+    pub gaps: i64,
+    /// Sequence numbers skipped.
+    pub lost: i64,
+    /// Chunks dropped because the buffer was full, or that failed the receiver.
+    pub overflowed: i64,
+    /// Chunks held back waiting for a missing sequence number.
+    pub held: u32,
+    /// Chunks waiting to be read.
+    pub buffered: u32,
+}
+
+enum End {
+    Closed,
+    /// Reason of the error the receiver failed with.
+    Failed(String),
+}
+
+struct Item {
+    chunk: actr_protocol::DataStream,
+    sender: actr_protocol::ActrId,
+    control: Option<Control>,
+    /// Sequence numbers skipped right before this chunk.
+    missed: u64,
+}
+
+struct Held {
+    item: Item,
+    arrived: Instant,
+}
+
+/// Restores sequence order within a window of held chunks.
+struct Reorder {
+    window: usize,
+    jitter: Option<Duration>,
+    /// Sequence number delivered next.
+    expected: u64,
+    held: BTreeMap<u64, Held>,
+}
+
+impl Reorder {
+    /// When the longest-held chunk is due for release, if a jitter deadline applies.
+    fn deadline(&self) -> Option<Instant> {
+        let jitter = self.jitter?;
+        self.held.values().map(|held| held.arrived + jitter).min()
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    received: u64,
+    delivered: u64,
+    duplicates: u64,
+    reordered: u64,
+    gaps: u64,
+    lost: u64,
+    overflowed: u64,
+}
+
+#[derive(Default)]
+struct State {
+    buffer: VecDeque<Item>,
+    reorder: Option<Reorder>,
+    counters: Counters,
+    end: Option<End>,
+}
+
+struct Shared {
+    stream_id: String,
+    capacity: usize,
+    overflow: StreamOverflow,
+    lease: Arc<Lease>,
+    state: Mutex<State>,
+    /// Woken when a chunk is buffered or the receiver ends.
+    readable: Notify,
+    /// Woken when a slot frees up or the receiver ends.
+    writable: Notify,
+}
+
+impl Shared {
+    /// Take a chunk delivered by the runtime, waiting for room under `Block`.
+    async fn push(&self, chunk: actr_protocol::DataStream, sender: actr_protocol::ActrId) {
+        self.lease.touch(&sender);
+        let item = Item {
+            control: Control::of(&chunk),
+            chunk,
+            sender,
+            missed: 0,
+        };
+        loop {
+            let writable = self.writable.notified();
+            tokio::pin!(writable);
+            writable.as_mut().enable();
+            {
+                let mut state = self.state.lock();
+                if state.end.is_some() {
+                    return;
+                }
+                let blocked = self.overflow == StreamOverflow::Block
+                    && item.control.is_none()
+                    && state.buffer.len() >= self.capacity;
+                if !blocked {
+                    self.accept(&mut state, item);
+                    drop(state);
+                    self.readable.notify_waiters();
+                    return;
+                }
+            }
+            writable.await;
+        }
+    }
+
+    /// Pass `item` on in arrival order, or hold it until its sequence number is due.
+    fn accept(&self, state: &mut State, item: Item) {
+        if item.control.is_none() {
+            state.counters.received += 1;
+        }
+        let Some(reorder) = state.reorder.as_mut() else {
+            self.release(state, item);
+            return;
+        };
+
+        let sequence = item.chunk.sequence;
+        let expected = reorder.expected;
+        if sequence < expected || reorder.held.contains_key(&sequence) {
+            state.counters.duplicates += 1;
+            return;
+        }
+        if sequence > expected {
+            state.counters.reordered += 1;
+        }
+        reorder.held.insert(
+            sequence,
+            Held {
+                item,
+                arrived: Instant::now(),
+            },
+        );
+        self.drain(state, false);
+    }
+
+    /// Release held chunks that are due: the expected one, and past a missing sequence number
+    /// once the window is full, the jitter deadline has passed or `flush` is set.
+    fn drain(&self, state: &mut State, flush: bool) {
+        let now = Instant::now();
+        loop {
+            let Some(reorder) = state.reorder.as_mut() else {
+                return;
+            };
+            let Some(&first) = reorder.held.keys().next() else {
+                return;
+            };
+            let expected = reorder.expected;
+            if !flush
+                && first != expected
+                && reorder.held.len() <= reorder.window
+                && reorder.deadline().is_none_or(|deadline| deadline > now)
+            {
+                return;
+            }
+
+            let Some((_, held)) = reorder.held.pop_first() else {
+                return;
+            };
+            reorder.expected = first + 1;
+            let missed = first - expected;
+            if missed > 0 {
+                state.counters.gaps += 1;
+                state.counters.lost += missed;
+                tracing::debug!(
+                    "stream {} skipped {missed} missing chunks before sequence {first}",
+                    self.stream_id
+                );
+            }
+            self.release(
+                state,
+                Item {
+                    missed,
+                    ..held.item
+                },
+            );
+        }
+    }
+
+    /// Buffer a chunk that is due for reading, applying `overflow` when the buffer is full.
+    fn release(&self, state: &mut State, item: Item) {
+        if state.end.is_some() {
+            return;
+        }
+        match item.control {
+            Some(Control::End) => {
+                state.end = Some(End::Closed);
+                self.lease.close(StreamCloseReason::Ended);
+                return;
+            }
+            Some(Control::Abort(reason)) => {
+                state.end = Some(End::Failed(
+                    Control::aborted(&self.stream_id, &reason).reason,
+                ));
+                self.lease.close(StreamCloseReason::Aborted);
+                return;
+            }
+            None => {}
+        }
+        if state.buffer.len() < self.capacity {
+            state.buffer.push_back(item);
+            return;
+        }
+        match self.overflow {
+            // Room was waited for before the chunk was taken; chunks released along with it
+            // after a reorder may briefly exceed the buffer.
+            StreamOverflow::Block => state.buffer.push_back(item),
+            StreamOverflow::DropOldest => {
+                state.buffer.pop_front();
+                state.buffer.push_back(item);
+                state.counters.overflowed += 1;
+            }
+            StreamOverflow::DropNewest => state.counters.overflowed += 1,
+            StreamOverflow::Error => {
+                state.counters.overflowed += 1;
+                state.end = Some(End::Failed(
+                    actr_error(
+                        ErrorCode::StreamOverflow,
+                        format!(
+                            "stream {} overflowed its buffer of {} chunks",
+                            self.stream_id, self.capacity
+                        ),
+                    )
+                    .reason,
+                ));
+                self.lease.close(StreamCloseReason::Overflowed);
+            }
+        }
+    }
+
+    /// End the receiver unless it has already ended, discarding buffered chunks, and close its
+    /// registration.
+    fn close(&self) {
+        let mut state = self.state.lock();
+        state.buffer.clear();
+        if let Some(reorder) = state.reorder.as_mut() {
+            reorder.held.clear();
+        }
+        if state.end.is_none() {
+            state.end = Some(End::Closed);
+        }
+        drop(state);
+        self.lease.close(StreamCloseReason::Unregistered);
+        self.readable.notify_waiters();
+        self.writable.notify_waiters();
+    }
+
+    /// End the receiver once its registration has closed, keeping the chunks it holds readable.
+    fn finish(&self) {
+        let mut state = self.state.lock();
+        self.drain(&mut state, true);
+        if state.end.is_none() {
+            state.end = Some(End::Closed);
+        }
+        drop(state);
+        self.readable.notify_waiters();
+        self.writable.notify_waiters();
+    }
+}
+
+/// Receiving end of a stream, created by `ctx.receiveStream`.
+///
+/// Chunks are read with `next()` in arrival order, or in sequence order with a reorder window,
+/// which resolves `null` once the receiver is closed. The stream handler stays registered until the
+/// stream ends, `close()` is called, the receiver is garbage-collected or its registration closes
+/// by itself.
+#[napi]
+pub struct StreamReceiver {
+    shared: Arc<Shared>,
+    serial_numbers: SerialNumberMode,
+}
+
+impl StreamReceiver {
+    /// Register a buffered handler for `stream_id` on `ctx`, supervised by `router`.
+    pub async fn register(
+        router: Arc<Router>,
+        ctx: &RuntimeContext,
+        stream_id: String,
+        options: StreamReceiverOptions,
+    ) -> Result<Self> {
+        let window = options.reorder_window.unwrap_or(0);
+        if window == 0 && options.jitter_ms.is_some() {
+            return Err(actr_error(
+                ErrorCode::InvalidArgument,
+                "jitterMs requires a reorderWindow",
+            ));
+        }
+        let start_sequence = options.start_sequence.unwrap_or(0);
+        if start_sequence < 0 {
+            return Err(actr_error(
+                ErrorCode::InvalidArgument,
+                format!("startSequence must not be negative, got {start_sequence}"),
+            ));
+        }
+
+        let shared = Arc::new(Shared {
+            stream_id: stream_id.clone(),
+            capacity: options
+                .high_water_mark
+                .unwrap_or(DEFAULT_HIGH_WATER_MARK)
+                .max(1) as usize,
+            overflow: options.overflow.unwrap_or(StreamOverflow::Block),
+            lease: router.streams().lease(&stream_id),
+            state: Mutex::new(State {
+                reorder: (window > 0).then(|| Reorder {
+                    window: window as usize,
+                    jitter: options
+                        .jitter_ms
+                        .map(|jitter_ms| Duration::from_millis(jitter_ms as u64)),
+                    expected: start_sequence as u64,
+                    held: BTreeMap::new(),
+                }),
+                ..State::default()
+            }),
+            readable: Notify::new(),
+            writable: Notify::new(),
+        });
+
+        let handler = shared.clone();
+        ctx.register_stream(stream_id, move |chunk, sender| {
+            let handler = handler.clone();
+            Box::pin(async move {
+                handler.push(chunk, sender).await;
+                Ok(())
+            })
+        })
+        .await
+        .map_err(crate::error::protocol_error_to_napi)?;
+
+        let serial_numbers = router.serial_numbers();
+        let finished = shared.clone();
+        crate::lease::supervise(
+            router,
+            ctx.clone(),
+            shared.lease.clone(),
+            Lifetime::new(options.idle_timeout_ms, options.peer_check_ms),
+            move |_, _| finished.finish(),
+        );
+        Ok(Self {
+            shared,
+            serial_numbers,
+        })
+    }
+}
+
+#[napi]
+impl StreamReceiver {
+    /// Next chunk, waiting for one to arrive; `null` once the receiver is closed or the sender
+    /// ended the stream. Rejects with `STREAM_ABORTED` when the sender aborted the stream, or
+    /// `STREAM_OVERFLOW` when the `Error` overflow policy was hit, after the buffered chunks.
+    #[napi]
+    pub async fn next(&self) -> Result<Option<StreamSignal>> {
+        loop {
+            let readable = self.shared.readable.notified();
+            tokio::pin!(readable);
+            readable.as_mut().enable();
+            let deadline = {
+                let mut state = self.shared.state.lock();
+                self.shared.drain(&mut state, false);
+                if let Some(item) = state.buffer.pop_front() {
+                    state.counters.delivered += 1;
+                    // A consumer catching up on a blocked sender keeps the stream alive.
+                    self.shared.lease.touch(&item.sender);
+                    drop(state);
+                    self.shared.writable.notify_waiters();
+                    return Ok(Some(StreamSignal {
+                        chunk: item.chunk.into(),
+                        sender: self.serial_numbers.id_to_js(item.sender),
+                        end: None,
+                        missed: (item.missed > 0).then_some(item.missed as i64),
+                    }));
+                }
+                match &state.end {
+                    Some(End::Closed) => return Ok(None),
+                    Some(End::Failed(reason)) => return Err(Error::from_reason(reason.clone())),
+                    None => {}
+                }
+                state.reorder.as_ref().and_then(Reorder::deadline)
+            };
+            match deadline {
+                Some(deadline) => {
+                    tokio::select! {
+                        _ = readable => {}
+                        _ = tokio::time::sleep_until(deadline.into()) => {}
+                    }
+                }
+                None => readable.await,
+            }
+        }
+    }
+
+    /// Stop receiving and unregister the stream handler. Buffered chunks are discarded.
+    #[napi]
+    pub async fn close(&self) -> Result<()> {
+        self.shared.close();
+        self.shared.lease.closed().await;
+        Ok(())
+    }
+
+    /// Why the receiver's registration closed, once it has.
+    #[napi(getter)]
+    pub fn close_reason(&self) -> Option<StreamCloseReason> {
+        self.shared.lease.reason()
+    }
+
+    #[napi(getter)]
+    pub fn stream_id(&self) -> String {
+        self.shared.stream_id.clone()
+    }
+
+    /// Chunks waiting to be read.
+    #[napi(getter)]
+    pub fn buffered(&self) -> u32 {
+        self.shared.state.lock().buffer.len() as u32
+    }
+
+    /// Chunks that arrived while the buffer was full and were dropped or failed the receiver.
+    #[napi(getter)]
+    pub fn overflow_count(&self) -> i64 {
+        self.shared.state.lock().counters.overflowed as i64
+    }
+
+    /// Delivery, loss and reorder counters of the stream.
+    #[napi]
+    pub fn stats(&self) -> StreamStats {
+        let state = self.shared.state.lock();
+        let counters = &state.counters;
+        StreamStats {
+            received: counters.received as i64,
+            delivered: counters.delivered as i64,
+            duplicates: counters.duplicates as i64,
+            reordered: counters.reordered as i64,
+            gaps: counters.gaps as i64,
+            lost: counters.lost as i64,
+            overflowed: counters.overflowed as i64,
+            held: state
+                .reorder
+                .as_ref()
+                .map_or(0, |reorder| reorder.held.len() as u32),
+            buffered: state.buffer.len() as u32,
+        }
+    }
+}
+
+impl Drop for StreamReceiver {
+    fn drop(&mut self) {
+        self.shared.close();
+    }
+}
+
+/// Options for `ctx.openStream`.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct StreamWriterOptions {
+    /// Metadata sent with every chunk.
+    pub metadata: Option<HashMap<String, String>>,
+    /// Sequence number of the first chunk (default 0).
+    #[napi(js_name = "startSequence")]
+    pub start_sequence: Option<i64>,
+}
+
+/// Sending end of a stream, created by `ctx.openStream`.
+///
+/// Chunks get consecutive sequence numbers and the current time as timestamp; a write that fails
+/// does not use up its number. Writes are sent in call order; once `end()` or `abort()` has been
+/// sent, further writes reject with `INVALID_STATE`.
+#[napi]
+pub struct StreamWriter {
+    ctx: RuntimeContext,
+    target: actr_protocol::ActrId,
+    serial_numbers: SerialNumberMode,
+    stream_id: String,
+    metadata: Vec<actr_protocol::MetadataEntry>,
+    /// Next sequence number, held while a chunk is sent so chunks go out in order.
+    next_sequence: tokio::sync::Mutex<u64>,
+    finished: AtomicBool,
+}
+
+impl StreamWriter {
+    pub fn new(
+        ctx: &RuntimeContext,
+        target: actr_protocol::ActrId,
+        serial_numbers: SerialNumberMode,
+        stream_id: String,
+        options: StreamWriterOptions,
+    ) -> Result<Self> {
+        let start_sequence = options.start_sequence.unwrap_or(0);
+        if start_sequence < 0 {
+            return Err(actr_error(
+                ErrorCode::InvalidArgument,
+                format!("startSequence must not be negative, got {start_sequence}"),
+            ));
+        }
+        let metadata = entries(options.metadata)?;
+        Ok(Self {
+            ctx: ctx.clone(),
+            target,
+            serial_numbers,
+            stream_id,
+            metadata,
+            next_sequence: tokio::sync::Mutex::new(start_sequence as u64),
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    /// Send one chunk with the next sequence number; `finish` marks the stream finished.
+    async fn send(
+        &self,
+        payload: bytes::Bytes,
+        metadata: Vec<actr_protocol::MetadataEntry>,
+        finish: bool,
+    ) -> Result<()> {
+        let mut next_sequence = self.next_sequence.lock().await;
+        if self.finished.load(Ordering::Acquire) {
+            return Err(actr_error(
+                ErrorCode::InvalidState,
+                format!("stream {} has already ended", self.stream_id),
+            ));
+        }
+        let chunk = actr_protocol::DataStream {
+            stream_id: self.stream_id.clone(),
+            sequence: *next_sequence,
+            payload,
+            metadata: self.metadata.iter().cloned().chain(metadata).collect(),
+            timestamp_ms: Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as i64,
+            ),
+        };
+        self.ctx
+            .send_data_stream(&actr_framework::Dest::Actor(self.target.clone()), chunk)
+            .await
+            .map_err(crate::error::protocol_error_to_napi)?;
+        // Only a sent chunk takes its number; a failed write leaves no gap at the receiver, and a
+        // retry that turns out to repeat a delivered chunk is dropped there as a duplicate.
+        *next_sequence += 1;
+        if finish {
+            self.finished.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+}
+
+#[napi]
+impl StreamWriter {
+    /// Send `payload` as the next chunk, with `metadata` added to the stream's metadata.
+    #[napi]
+    pub async fn write(
+        &self,
+        payload: Buffer,
+        metadata: Option<HashMap<String, String>>,
+    ) -> Result<()> {
+        let metadata = entries(metadata)?;
+        self.send(bytes::Bytes::from(payload.to_vec()), metadata, false)
+            .await
+    }
+
+    /// Finish the stream; the receiver closes once it has read every chunk.
+    #[napi]
+    pub async fn end(&self) -> Result<()> {
+        self.send(bytes::Bytes::new(), control_entry("end"), true)
+            .await
+    }
+
+    /// Fail the stream; the receiver rejects with `STREAM_ABORTED` carrying `reason`.
+    #[napi]
+    pub async fn abort(&self, reason: Option<String>) -> Result<()> {
+        let reason = reason.unwrap_or_else(|| "aborted".to_string());
+        self.send(bytes::Bytes::from(reason), control_entry("abort"), true)
+            .await
+    }
+
+    #[napi(getter)]
+    pub fn stream_id(&self) -> String {
+        self.stream_id.clone()
+    }
+
+    #[napi(getter)]
+    pub fn target(&self) -> ActrId {
+        self.serial_numbers.id_to_js(self.target.clone())
+    }
+
+    /// Whether `end()` or `abort()` has been sent.
+    #[napi(getter)]
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Acquire)
+    }
+}
+
+fn control_entry(value: &str) -> Vec<actr_protocol::MetadataEntry> {
+    vec![actr_protocol::MetadataEntry {
+        key: STREAM_CONTROL_KEY.to_string(),
+        value: value.to_string(),
+    }]
+}
+
+/// Convert user metadata, rejecting the reserved control key.
+fn entries(metadata: Option<HashMap<String, String>>) -> Result<Vec<actr_protocol::MetadataEntry>> {
+    metadata
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| {
+            if key == STREAM_CONTROL_KEY {
+                return Err(actr_error(
+                    ErrorCode::InvalidArgument,
+                    format!("metadata key {STREAM_CONTROL_KEY} is reserved"),
+                ));
+            }
+            Ok(actr_protocol::MetadataEntry { key, value })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lease::Leases;
+
+    fn shared(window: usize, jitter: Option<Duration>, start_sequence: u64) -> Shared {
+        Shared {
+            stream_id: "s".to_string(),
+            capacity: 64,
+            overflow: StreamOverflow::Block,
+            lease: Leases::default().lease("s"),
+            state: Mutex::new(State {
+                reorder: Some(Reorder {
+                    window,
+                    jitter,
+                    expected: start_sequence,
+                    held: BTreeMap::new(),
+                }),
+                ..State::default()
+            }),
+            readable: Notify::new(),
+            writable: Notify::new(),
+        }
+    }
+
+    fn push(shared: &Shared, sequence: u64) {
+        let item = Item {
+            chunk: actr_protocol::DataStream {
+                stream_id: "s".to_string(),
+                sequence,
+                payload: bytes::Bytes::new(),
+                metadata: Vec::new(),
+                timestamp_ms: None,
+            },
+            sender: crate::identity::parse_id("1@1/acme+echo").unwrap(),
+            control: None,
+            missed: 0,
+        };
+        shared.accept(&mut shared.state.lock(), item);
+    }
+
+    /// Sequence numbers and gaps of the chunks ready for reading.
+    fn delivered(shared: &Shared) -> Vec<(u64, u64)> {
+        shared
+            .state
+            .lock()
+            .buffer
+            .drain(..)
+            .map(|item| (item.chunk.sequence, item.missed))
+            .collect()
+    }
+
+    #[test]
+    fn restores_sequence_order() {
+        let shared = shared(4, None, 0);
+        push(&shared, 1);
+        push(&shared, 2);
+        assert!(delivered(&shared).is_empty());
+        push(&shared, 0);
+        assert_eq!(delivered(&shared), [(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(shared.state.lock().counters.reordered, 2);
+    }
+
+    #[test]
+    fn first_arrival_does_not_fix_the_start() {
+        let shared = shared(4, None, 0);
+        push(&shared, 3);
+        push(&shared, 1);
+        push(&shared, 0);
+        push(&shared, 2);
+        assert_eq!(delivered(&shared), [(0, 0), (1, 0), (2, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn starts_at_the_configured_sequence() {
+        let shared = shared(4, None, 10);
+        push(&shared, 9);
+        push(&shared, 10);
+        assert_eq!(delivered(&shared), [(10, 0)]);
+        assert_eq!(shared.state.lock().counters.duplicates, 1);
+    }
+
+    #[test]
+    fn skips_a_gap_once_the_window_is_full() {
+        let shared = shared(2, None, 0);
+        push(&shared, 1);
+        push(&shared, 2);
+        assert!(delivered(&shared).is_empty());
+        push(&shared, 3);
+        assert_eq!(delivered(&shared), [(1, 1), (2, 0), (3, 0)]);
+
+        let state = shared.state.lock();
+        assert_eq!((state.counters.gaps, state.counters.lost), (1, 1));
+    }
+
+    #[test]
+    fn drops_late_and_repeated_chunks() {
+        let shared = shared(2, None, 0);
+        push(&shared, 0);
+        push(&shared, 0);
+        push(&shared, 2);
+        push(&shared, 2);
+        assert_eq!(delivered(&shared), [(0, 0)]);
+        assert_eq!(shared.state.lock().counters.duplicates, 2);
+    }
+
+    #[test]
+    fn skips_a_gap_once_the_jitter_deadline_passes() {
+        let shared = shared(8, Some(Duration::ZERO), 0);
+        push(&shared, 2);
+        assert_eq!(delivered(&shared), [(2, 2)]);
+    }
+
+    #[test]
+    fn finish_flushes_held_chunks() {
+        let shared = shared(8, None, 0);
+        push(&shared, 1);
+        push(&shared, 3);
+        shared.finish();
+        assert_eq!(delivered(&shared), [(1, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn overflow_error_closes_the_registration_as_overflowed() {
+        let shared = Shared {
+            capacity: 1,
+            overflow: StreamOverflow::Error,
+            state: Mutex::new(State::default()),
+            ..shared(0, None, 0)
+        };
+        push(&shared, 0);
+        push(&shared, 1);
+        assert!(shared.lease.reason() == Some(StreamCloseReason::Overflowed));
+    }
+}
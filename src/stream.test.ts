@@ -0,0 +1,151 @@
+import { describe, it, expect } from 'vitest';
+import { ActrError, ErrorCode } from '../typescript/error';
+import { StreamReceiver, StreamWriter } from '../typescript/stream';
+
+const sender = { realm: { realmId: 1 }, serialNumber: 1n, type: { manufacturer: 'acme', name: 'Echo' } };
+
+function signal(sequence: number) {
+  return {
+    chunk: { streamId: 's', sequence, payload: Buffer.from([sequence]), metadata: [] },
+    sender,
+    missed: 0,
+  };
+}
+
+// Native receiver yielding `count` chunks, then ending or failing with `error`.
+function fakeReceiver(count: number, error?: Error) {
+  let sent = 0;
+  return {
+    streamId: 's',
+    closed: false,
+    overflowCount: error?.message.includes('STREAM_OVERFLOW') ? 1 : 0,
+    async next() {
+      if (sent < count) {
+        return signal(sent++);
+      }
+      if (error) {
+        throw error;
+      }
+      return null;
+    },
+    async close() {
+      this.closed = true;
+    },
+  };
+}
+
+async function collect(receiver: StreamReceiver): Promise<{ sequences: number[]; error?: unknown }> {
+  const sequences: number[] = [];
+  try {
+    for await (const { chunk } of receiver) {
+      sequences.push(chunk.sequence);
+    }
+  } catch (error) {
+    return { sequences, error };
+  }
+  return { sequences };
+}
+
+describe('StreamReceiver', () => {
+  it('should end iteration after the chunks received before the stream ended', async () => {
+    const { sequences, error } = await collect(new StreamReceiver(fakeReceiver(3) as any));
+    expect(sequences).toEqual([0, 1, 2]);
+    expect(error).toBeUndefined();
+  });
+
+  it('should fail with STREAM_ABORTED after the chunks sent before abort()', async () => {
+    const aborted = new Error('[ACTR_STREAM_ABORTED] stream s aborted by sender: disk full');
+    const { sequences, error } = await collect(new StreamReceiver(fakeReceiver(2, aborted) as any));
+    expect(sequences).toEqual([0, 1]);
+    expect(error).toBeInstanceOf(ActrError);
+    expect((error as ActrError).code).toBe(ErrorCode.StreamAborted);
+    expect((error as ActrError).message).toBe('stream s aborted by sender: disk full');
+  });
+
+  it('should fail with STREAM_OVERFLOW once the buffered chunks are read', async () => {
+    const overflowed = new Error('[ACTR_STREAM_OVERFLOW] stream s overflowed its buffer of 2 chunks');
+    const receiver = new StreamReceiver(fakeReceiver(2, overflowed) as any);
+    const { sequences, error } = await collect(receiver);
+    expect(sequences).toEqual([0, 1]);
+    expect((error as ActrError).code).toBe(ErrorCode.StreamOverflow);
+    expect(receiver.overflowCount).toBe(1);
+  });
+
+  it('should close the receiver when iteration stops early', async () => {
+    const native = fakeReceiver(3);
+    for await (const _ of new StreamReceiver(native as any)) {
+      break;
+    }
+    expect(native.closed).toBe(true);
+  });
+
+  it('should end and fail the Readable like the receiver', async () => {
+    const ended = new StreamReceiver(fakeReceiver(2) as any).toReadable();
+    const payloads: Buffer[] = [];
+    for await (const payload of ended) {
+      payloads.push(payload);
+    }
+    expect(Buffer.concat(payloads)).toEqual(Buffer.from([0, 1]));
+
+    const aborted = new Error('[ACTR_STREAM_ABORTED] stream s aborted by sender: gone');
+    const failed = new StreamReceiver(fakeReceiver(1, aborted) as any).toReadable();
+    await expect(async () => {
+      for await (const _ of failed) {
+        // drain
+      }
+    }).rejects.toMatchObject({ code: ErrorCode.StreamAborted });
+  });
+});
+
+describe('StreamWriter', () => {
+  function fakeWriter() {
+    const calls: string[] = [];
+    return {
+      calls,
+      streamId: 's',
+      isFinished: false,
+      async write(payload: Buffer) {
+        calls.push(`write ${payload.toString()}`);
+      },
+      async end() {
+        calls.push('end');
+        this.isFinished = true;
+      },
+      async abort(reason?: string) {
+        calls.push(`abort ${reason}`);
+        this.isFinished = true;
+      },
+    };
+  }
+
+  it('should end the stream when the Writable finishes', async () => {
+    const native = fakeWriter();
+    const writable = new StreamWriter(native as any).toWritable();
+    await new Promise<void>((resolve, reject) => {
+      writable.on('finish', resolve).on('error', reject);
+      writable.write(Buffer.from('a'));
+      writable.end(Buffer.from('b'));
+    });
+    expect(native.calls).toEqual(['write a', 'write b', 'end']);
+  });
+
+  it('should abort the stream when the Writable is destroyed with an error', async () => {
+    const native = fakeWriter();
+    const writable = new StreamWriter(native as any).toWritable();
+    await new Promise<void>((resolve) => {
+      writable.on('close', resolve).on('error', () => undefined);
+      writable.destroy(new Error('upstream failed'));
+    });
+    expect(native.calls).toEqual(['abort upstream failed']);
+  });
+
+  it('should abort the stream when the Writable is destroyed without an error', async () => {
+    const native = fakeWriter();
+    const writable = new StreamWriter(native as any).toWritable();
+    await new Promise<void>((resolve) => {
+      writable.on('close', resolve);
+      writable.destroy();
+    });
+    expect(native.calls).toEqual(['abort destroyed']);
+  });
+});
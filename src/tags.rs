@@ -0,0 +1,66 @@
+// Self-advertised tags
+//
+// Discovery only matches on manufacturer+name, so actr-ts nodes answer the reserved `actr.Tags`
+// route with their key/value tags (version, zone, capacity, ...). The discovering node queries
+// candidates on demand and caches the answers, which lets tag filters run on top of the runtime's
+// discovery without a separate registry. Peers that are not actr-ts nodes report no tags.
+//
+// Tags are encoded as a metadata frame (see `headers`) with an empty payload.
+
+use bytes::Bytes;
+use std::collections::HashMap;
+
+use crate::error::{ErrorCode, actr_error};
+
+/// Reserved route key answered with the node's tags.
+pub const TAGS_ROUTE_KEY: &str = "actr.Tags";
+
+/// Table of `Actr.toml` read as the node's initial tags.
+const CONFIG_TABLE: &str = "tags";
+
+pub type Tags = HashMap<String, String>;
+
+pub fn encode(tags: &Tags) -> Bytes {
+    let mut entries: Vec<(String, String)> =
+        tags.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    entries.sort();
+    crate::headers::encode(&entries, Bytes::new())
+}
+
+pub fn decode(payload: Bytes) -> napi::Result<Tags> {
+    Ok(crate::headers::decode(payload)?.0.into_iter().collect())
+}
+
+/// Whether `tags` contains every entry of `filter`.
+pub fn matches(tags: &Tags, filter: &Tags) -> bool {
+    filter.iter().all(|(k, v)| tags.get(k) == Some(v))
+}
+
+/// Read the `[tags]` table of a config file. Non-string values are kept in their TOML form.
+pub fn from_config_file(path: &str) -> napi::Result<Tags> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| actr_error(ErrorCode::Config, format!("failed to read {path}: {e}")))?;
+    let config: toml::Table = content
+        .parse()
+        .map_err(|e| actr_error(ErrorCode::Config, format!("failed to parse {path}: {e}")))?;
+
+    let Some(table) = config.get(CONFIG_TABLE) else {
+        return Ok(Tags::new());
+    };
+    let table = table.as_table().ok_or_else(|| {
+        actr_error(
+            ErrorCode::Config,
+            format!("[{CONFIG_TABLE}] in {path} must be a table"),
+        )
+    })?;
+    Ok(table
+        .iter()
+        .map(|(k, v)| {
+            let value = match v {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (k.clone(), value)
+        })
+        .collect())
+}
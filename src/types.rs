@@ -1,5 +1,8 @@
 use napi::bindgen_prelude::*;
 use napi_derive::napi;
+use std::collections::HashMap;
+
+use crate::error::ErrorCode;
 
 // Realm
 #[napi(object)]
@@ -53,28 +56,80 @@ impl From<ActrType> for actr_protocol::ActrType {
 #[napi(object)]
 pub struct ActrId {
     pub realm: Realm,
+    /// Full u64 serial number. Emitted as `bigint` unless the system selects
+    /// `SerialNumberMode.Number`.
     #[napi(js_name = "serialNumber")]
-    pub serial_number: i64, // i64 for JavaScript number safety
+    pub serial_number: Either<BigInt, f64>,
     pub r#type: ActrType,
 }
 
-impl From<actr_protocol::ActrId> for ActrId {
-    fn from(id: actr_protocol::ActrId) -> Self {
-        Self {
+/// How `ActrId.serialNumber` is handed to JS, chosen per system with `serialNumberMode`.
+#[napi]
+pub enum SerialNumberMode {
+    /// Always a `bigint` (lossless).
+    BigInt,
+    /// Always a `number`. Serial numbers above `Number.MAX_SAFE_INTEGER` lose precision, and an
+    /// ActrId carrying such a number is rejected when passed back in.
+    Number,
+}
+
+impl SerialNumberMode {
+    /// `id` as handed to JS, with its serial number in this mode.
+    pub fn id_to_js(self, id: actr_protocol::ActrId) -> ActrId {
+        let serial_number = match self {
+            SerialNumberMode::BigInt => Either::A(BigInt::from(id.serial_number)),
+            SerialNumberMode::Number => Either::B(id.serial_number as f64),
+        };
+        ActrId {
             realm: id.realm.into(),
-            serial_number: id.serial_number as i64,
+            serial_number,
             r#type: id.r#type.into(),
         }
     }
 }
 
-impl From<ActrId> for actr_protocol::ActrId {
-    fn from(id: ActrId) -> Self {
-        Self {
+/// Largest integer a JS number represents exactly (2^53 - 1).
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+fn serial_number_from_js(serial_number: &Either<BigInt, f64>) -> Result<u64> {
+    match serial_number {
+        Either::A(big) => {
+            let (negative, value, lossless) = big.get_u64();
+            if negative || !lossless {
+                return Err(crate::error::actr_error(
+                    ErrorCode::InvalidArgument,
+                    "serialNumber must be a bigint in the range 0..2^64",
+                ));
+            }
+            Ok(value)
+        }
+        Either::B(number) => {
+            if !number.is_finite()
+                || number.fract() != 0.0
+                || *number < 0.0
+                || *number > MAX_SAFE_INTEGER as f64
+            {
+                return Err(crate::error::actr_error(
+                    ErrorCode::InvalidArgument,
+                    format!(
+                        "serialNumber {number} is not a safe non-negative integer; pass a bigint"
+                    ),
+                ));
+            }
+            Ok(*number as u64)
+        }
+    }
+}
+
+impl TryFrom<ActrId> for actr_protocol::ActrId {
+    type Error = napi::Error;
+
+    fn try_from(id: ActrId) -> Result<Self> {
+        Ok(Self {
             realm: id.realm.into(),
-            serial_number: id.serial_number as u64,
+            serial_number: serial_number_from_js(&id.serial_number)?,
             r#type: id.r#type.into(),
-        }
+        })
     }
 }
 
@@ -135,6 +190,10 @@ pub struct DataStream {
 pub struct StreamSignal {
     pub chunk: DataStream,
     pub sender: ActrId,
+    /// Set on the control chunk a sender's `end()` delivers to a `registerStream` callback.
+    pub end: Option<bool>,
+    /// Sequence numbers a reordering receiver skipped right before this chunk.
+    pub missed: Option<i64>,
 }
 
 impl From<actr_protocol::DataStream> for DataStream {
@@ -175,6 +234,139 @@ impl From<DataStream> for actr_protocol::DataStream {
     }
 }
 
+/// How a target is chosen among the discovered candidates of a type.
+#[napi]
+pub enum LoadBalanceStrategy {
+    /// Cycle through the candidates in order.
+    RoundRobin,
+    /// Pick a candidate uniformly at random.
+    Random,
+    /// Pick the candidate with the fewest calls in flight from this node.
+    LeastOutstanding,
+    /// Pick the candidate with the lowest observed response time; unmeasured candidates first.
+    LowestLatency,
+    /// Pick by hashing `hashKey`, so the same key keeps reaching the same candidate.
+    ConsistentHash,
+}
+
+/// A discovered actor with the tags it advertises.
+#[napi(object)]
+pub struct DiscoveredActor {
+    pub id: ActrId,
+    pub tags: HashMap<String, String>,
+}
+
+// DiscoverOptions
+/// Options for discovering candidates of a type.
+#[napi(object)]
+#[derive(Default)]
+pub struct DiscoverOptions {
+    /// Selection strategy; defaults to the node's `WorkloadOptions.loadBalance`.
+    #[napi(js_name = "loadBalance")]
+    pub load_balance: Option<LoadBalanceStrategy>,
+    /// Key hashed by `LoadBalanceStrategy.ConsistentHash`.
+    #[napi(js_name = "hashKey")]
+    pub hash_key: Option<String>,
+    /// Fewest candidates to wait for (default 1). More than 1 rejects with `INVALID_STATE` before
+    /// the node has started, e.g. in `onStart`.
+    #[napi(js_name = "minCandidates")]
+    pub min_candidates: Option<u32>,
+    /// How long to keep re-discovering until `minCandidates` are found, in ms. Rejects with
+    /// `NO_CANDIDATES` once it runs out (default 0: a single attempt).
+    #[napi(js_name = "waitMs")]
+    pub wait_ms: Option<u32>,
+    /// Only candidates advertising all of these tags.
+    pub tags: Option<HashMap<String, String>>,
+}
+
+impl DiscoverOptions {
+    pub fn selection(&self) -> crate::routing::Selection<'_> {
+        crate::routing::Selection {
+            strategy: self.load_balance,
+            hash_key: self.hash_key.as_deref(),
+            tags: self.tags.as_ref(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the caller asked to wait for candidates.
+    pub fn waits(&self) -> bool {
+        self.min_candidates.is_some() || self.wait_ms.is_some()
+    }
+}
+
+// CallOptions
+/// Per-call options for `callRaw`, `tellRaw`, `ActrRef.call` and `ActrRef.tell`.
+#[napi(object)]
+#[derive(Default)]
+pub struct CallOptions {
+    /// Timeout in ms; takes precedence over the positional `timeoutMs`.
+    #[napi(js_name = "timeoutMs")]
+    pub timeout_ms: Option<i64>,
+    /// Payload type; takes precedence over the positional `payloadType`.
+    #[napi(js_name = "payloadType")]
+    pub payload_type: Option<PayloadType>,
+    /// Request metadata delivered to the remote handler's `envelope.metadata`.
+    pub metadata: Option<HashMap<String, String>>,
+    /// Delivered as the `actr-idempotency-key` metadata entry.
+    #[napi(js_name = "idempotencyKey")]
+    pub idempotency_key: Option<String>,
+    /// Delivered as the `actr-priority` metadata entry.
+    pub priority: Option<i32>,
+    /// `CancellationToken.id` that aborts the call; set from `signal` by the TypeScript layer.
+    #[napi(js_name = "cancelTokenId")]
+    pub cancel_token_id: Option<i64>,
+    /// Selection strategy when calling by type; defaults to `WorkloadOptions.loadBalance`.
+    #[napi(js_name = "loadBalance")]
+    pub load_balance: Option<LoadBalanceStrategy>,
+    /// Key hashed by `LoadBalanceStrategy.ConsistentHash` when calling by type.
+    #[napi(js_name = "hashKey")]
+    pub hash_key: Option<String>,
+    /// Only call candidates advertising all of these tags when calling by type.
+    pub tags: Option<HashMap<String, String>>,
+    /// Retry policy; takes precedence over the node's route and default policies.
+    pub retry: Option<crate::retry::RetryPolicy>,
+    /// Share one request among identical calls in flight at the same time; defaults to whether
+    /// the route matches `WorkloadOptions.coalesceRoutes`.
+    pub coalesce: Option<bool>,
+    /// Set to `false` to bypass the response cache for this call.
+    pub cache: Option<bool>,
+}
+
+impl CallOptions {
+    /// Collect metadata entries, including the reserved idempotency and priority keys.
+    pub fn metadata_entries(&self) -> Result<Vec<(String, String)>> {
+        let mut entries: Vec<(String, String)> = self
+            .metadata
+            .iter()
+            .flatten()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        entries.sort();
+        if let Some(key) = &self.idempotency_key {
+            entries.push((crate::headers::IDEMPOTENCY_KEY.to_string(), key.clone()));
+        }
+        if let Some(priority) = self.priority {
+            entries.push((
+                crate::headers::PRIORITY_KEY.to_string(),
+                priority.to_string(),
+            ));
+        }
+
+        if entries.len() > u16::MAX as usize
+            || entries
+                .iter()
+                .any(|(k, v)| k.len() > u16::MAX as usize || v.len() > u32::MAX as usize)
+        {
+            return Err(crate::error::actr_error(
+                ErrorCode::InvalidArgument,
+                "call metadata is too large",
+            ));
+        }
+        Ok(entries)
+    }
+}
+
 // RpcEnvelopeBridge
 #[napi(object)]
 pub struct RpcEnvelopeBridge {
@@ -183,17 +375,64 @@ pub struct RpcEnvelopeBridge {
     pub payload: Buffer,
     #[napi(js_name = "requestId")]
     pub request_id: String,
+    pub metadata: Vec<MetadataEntry>,
+    /// W3C trace context propagated by the caller.
+    pub traceparent: Option<String>,
+    pub tracestate: Option<String>,
+    /// Timeout requested by the caller, in ms.
+    #[napi(js_name = "timeoutMs")]
+    pub timeout_ms: Option<i64>,
+    /// Absolute deadline (ms since Unix epoch) derived from `timeoutMs` on receipt.
+    #[napi(js_name = "deadlineMs")]
+    pub deadline_ms: Option<f64>,
+    /// ID of the calling actor, if known.
+    pub caller: Option<ActrId>,
+    /// Type of the calling actor, if known.
+    #[napi(js_name = "callerType")]
+    pub caller_type: Option<ActrType>,
 }
 
-impl From<actr_protocol::RpcEnvelope> for RpcEnvelopeBridge {
-    fn from(envelope: actr_protocol::RpcEnvelope) -> Self {
-        Self {
+impl RpcEnvelopeBridge {
+    pub fn new(
+        envelope: actr_protocol::RpcEnvelope,
+        caller: Option<actr_protocol::ActrId>,
+        serial_numbers: SerialNumberMode,
+    ) -> Result<Self> {
+        let timeout_ms = (envelope.timeout_ms > 0).then_some(envelope.timeout_ms);
+        let deadline_ms = timeout_ms.map(|timeout| {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as f64;
+            now + timeout as f64
+        });
+
+        let (framed_metadata, payload) =
+            crate::headers::decode(envelope.payload.unwrap_or_default())?;
+
+        Ok(Self {
             route_key: envelope.route_key,
-            payload: envelope
-                .payload
-                .map(|p| p.to_vec().into())
-                .unwrap_or_else(|| Buffer::from(vec![])),
+            payload: payload.to_vec().into(),
             request_id: envelope.request_id,
-        }
+            metadata: envelope
+                .metadata
+                .into_iter()
+                .map(|e| MetadataEntry {
+                    key: e.key,
+                    value: e.value,
+                })
+                .chain(
+                    framed_metadata
+                        .into_iter()
+                        .map(|(key, value)| MetadataEntry { key, value }),
+                )
+                .collect(),
+            traceparent: envelope.traceparent,
+            tracestate: envelope.tracestate,
+            timeout_ms,
+            deadline_ms,
+            caller_type: caller.as_ref().map(|id| id.r#type.clone().into()),
+            caller: caller.map(|id| serial_numbers.id_to_js(id)),
+        })
     }
 }
@@ -0,0 +1,173 @@
+//! Discovery watch: reports actors of one type appearing and disappearing.
+//!
+//! The runtime only offers request/response discovery, so a watch re-discovers the type on an
+//! interval and diffs the result against the previous round. A round that returns `count`
+//! actors may be truncated, so it reports no `left` events. Each round also refreshes the
+//! node's cached candidates for the type.
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::ThreadsafeFunction;
+use napi_derive::napi;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::error::ErrorCode;
+use crate::routing::Router;
+use crate::types::ActrId;
+use crate::workload::DynamicWorkload;
+
+/// Default time between discovery rounds of a watch, in milliseconds.
+const DEFAULT_WATCH_INTERVAL_MS: u32 = 5_000;
+
+/// An actor of the watched type appeared or disappeared.
+#[napi(object)]
+pub struct DiscoveryEvent {
+    #[napi(ts_type = "'joined' | 'left'")]
+    pub kind: String,
+    pub id: ActrId,
+}
+
+/// Options for `ActrRef.watch`.
+#[napi(object)]
+#[derive(Default)]
+pub struct WatchOptions {
+    /// Time between discovery rounds in ms (default 5000).
+    #[napi(js_name = "intervalMs")]
+    pub interval_ms: Option<u32>,
+    /// Number of candidates requested per round; defaults to `WorkloadOptions.discoveryCount`.
+    pub count: Option<u32>,
+}
+
+/// Handle of a running discovery watch.
+///
+/// The watch stops when `close()` is called, when the handle is garbage-collected, or when the
+/// node shuts down; no events are delivered after that.
+#[napi]
+pub struct DiscoveryWatch {
+    stop_tx: watch::Sender<bool>,
+    done_tx: Arc<watch::Sender<bool>>,
+}
+
+#[napi]
+impl DiscoveryWatch {
+    /// Stop watching.
+    #[napi]
+    pub fn close(&self) {
+        self.stop_tx.send_replace(true);
+    }
+
+    /// Whether the watch has stopped.
+    #[napi(getter)]
+    pub fn is_closed(&self) -> bool {
+        *self.done_tx.borrow()
+    }
+
+    /// Resolve once the watch has stopped and its last event has been delivered.
+    #[napi]
+    pub async fn closed(&self) {
+        let _ = self.done_tx.subscribe().wait_for(|done| *done).await;
+    }
+}
+
+impl Drop for DiscoveryWatch {
+    fn drop(&mut self) {
+        self.stop_tx.send_replace(true);
+    }
+}
+
+pub type WatchCallback = ThreadsafeFunction<DiscoveryEvent, ()>;
+
+/// Start watching `target_type`, delivering membership changes to `callback`.
+///
+/// Must be called from within the tokio runtime.
+pub fn spawn(
+    router: Arc<Router>,
+    actr_ref: Weak<actr_runtime::ActrRef<DynamicWorkload>>,
+    target_type: actr_protocol::ActrType,
+    callback: WatchCallback,
+    options: WatchOptions,
+) -> DiscoveryWatch {
+    let interval = Duration::from_millis(
+        options
+            .interval_ms
+            .unwrap_or(DEFAULT_WATCH_INTERVAL_MS)
+            .max(1) as u64,
+    );
+    let count = options.count.unwrap_or_else(|| router.discovery_count());
+    let (stop_tx, mut stop_rx) = watch::channel(false);
+    let done_tx = Arc::new(watch::channel(false).0);
+
+    let done = done_tx.clone();
+    tokio::spawn(async move {
+        let mut members: HashMap<String, actr_protocol::ActrId> = HashMap::new();
+        loop {
+            let running = actr_ref
+                .upgrade()
+                .is_some_and(|actr_ref| !actr_ref.is_shutting_down());
+            if !running || *stop_rx.borrow() {
+                break;
+            }
+
+            let discovered = match router.discover(None, &target_type, count).await {
+                Ok(ids) => Some(ids),
+                Err(e) if crate::error::has_code(&e, ErrorCode::NotFound) => Some(Vec::new()),
+                Err(e) => {
+                    tracing::debug!("discovery watch round failed: {e}");
+                    None
+                }
+            };
+            let stopped = *stop_rx.borrow();
+            if let Some(ids) = discovered
+                && !stopped
+            {
+                router.store(&target_type, &ids);
+                // A full page may have left actors out, so only a shorter one proves an absence.
+                let complete = ids.len() < count as usize;
+                let current: HashMap<String, actr_protocol::ActrId> = ids
+                    .into_iter()
+                    .map(|id| (crate::identity::id_to_string(&id), id))
+                    .collect();
+                if complete {
+                    for (key, id) in &members {
+                        if !current.contains_key(key) {
+                            emit(&callback, "left", router.id_to_js(id.clone())).await;
+                        }
+                    }
+                }
+                for (key, id) in &current {
+                    if !members.contains_key(key) {
+                        emit(&callback, "joined", router.id_to_js(id.clone())).await;
+                    }
+                }
+                if complete {
+                    members = current;
+                } else {
+                    members.extend(current);
+                }
+            }
+
+            tokio::select! {
+                _ = stop_rx.wait_for(|stopped| *stopped) => break,
+                _ = router.closed() => break,
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+        done.send_replace(true);
+    });
+
+    DiscoveryWatch { stop_tx, done_tx }
+}
+
+/// Deliver one event and wait for the listener to run, so events arrive in order and before
+/// the watch reports itself closed.
+async fn emit(callback: &WatchCallback, kind: &str, id: ActrId) {
+    let event = DiscoveryEvent {
+        kind: kind.to_string(),
+        id,
+    };
+    if let Err(e) = callback.call_async(Ok(event)).await {
+        tracing::debug!("discovery watch listener failed: {e}");
+    }
+}
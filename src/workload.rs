@@ -1,11 +1,26 @@
 use actr_framework::{Context, MessageDispatcher, Workload};
 use async_trait::async_trait;
 use napi::bindgen_prelude::*;
-use napi::threadsafe_function::{ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi::threadsafe_function::ThreadsafeFunction;
+use napi_derive::napi;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::breaker::CircuitBreakerOptions;
+use crate::cache::ResponseCacheOptions;
+use crate::cancel::RequestScope;
 use crate::context::ContextBridge;
-use crate::types::RpcEnvelopeBridge;
+use crate::hedge::HedgePolicy;
+use crate::retry::RetryPolicy;
+use crate::routing::Router;
+use crate::types::{LoadBalanceStrategy, RpcEnvelopeBridge};
+
+/// Default timeout for `onStart` / `onStop`, in milliseconds.
+const DEFAULT_LIFECYCLE_TIMEOUT_MS: u32 = 30_000;
+
+/// ThreadsafeFunction for lifecycle hooks: ContextBridge -> Promise<void>.
+type LifecycleThreadsafeFunction = ThreadsafeFunction<ContextBridge, Promise<()>>;
 
 /// ThreadsafeFunction for dispatch: (ContextBridge, RpcEnvelopeBridge) -> Promise<Buffer>.
 type DispatchThreadsafeFunction = ThreadsafeFunction<
@@ -14,24 +29,70 @@ type DispatchThreadsafeFunction = ThreadsafeFunction<
     FnArgs<(ContextBridge, ObjectRef<false>)>,
 >;
 
+/// Options applied when attaching a workload.
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct WorkloadOptions {
+    /// Maximum time `onStart` may take before the node fails to start (default 30000).
+    #[napi(js_name = "startTimeoutMs")]
+    pub start_timeout_ms: Option<u32>,
+    /// Maximum time `onStop` may take before shutdown proceeds without it (default 30000).
+    #[napi(js_name = "stopTimeoutMs")]
+    pub stop_timeout_ms: Option<u32>,
+    /// How long discovered candidates are reused when calling by type (default 30000).
+    #[napi(js_name = "targetCacheTtlMs")]
+    pub target_cache_ttl_ms: Option<u32>,
+    /// Number of candidates requested per discovery when calling by type (default 8).
+    #[napi(js_name = "discoveryCount")]
+    pub discovery_count: Option<u32>,
+    /// Default strategy for picking a candidate when calling by type (default `RoundRobin`).
+    #[napi(js_name = "loadBalance")]
+    pub load_balance: Option<LoadBalanceStrategy>,
+    /// Ring points per actor for consistent hashing (default 160).
+    #[napi(js_name = "virtualNodes")]
+    pub virtual_nodes: Option<u32>,
+    /// Retry policy for outbound calls without a more specific one (default: no retries).
+    pub retry: Option<RetryPolicy>,
+    /// Retry policies by route-key prefix; the longest matching prefix wins.
+    #[napi(js_name = "retryRoutes")]
+    pub retry_routes: Option<HashMap<String, RetryPolicy>>,
+    /// Per-target circuit breakers for outbound calls (default: disabled).
+    #[napi(js_name = "circuitBreaker")]
+    pub circuit_breaker: Option<CircuitBreakerOptions>,
+    /// Idempotent routes by route-key prefix, hedged as described by their policy.
+    #[napi(js_name = "hedgeRoutes")]
+    pub hedge_routes: Option<HashMap<String, HedgePolicy>>,
+    /// Route-key prefixes whose identical concurrent calls share one request.
+    #[napi(js_name = "coalesceRoutes")]
+    pub coalesce_routes: Option<Vec<String>>,
+    /// Client-side cache of responses of idempotent routes (default: disabled).
+    #[napi(js_name = "responseCache")]
+    pub response_cache: Option<ResponseCacheOptions>,
+}
+
 pub struct DynamicWorkload {
-    on_start_fn: Arc<ThreadsafeFunction<ContextBridge>>,
-    on_stop_fn: Arc<ThreadsafeFunction<ContextBridge>>,
+    on_start_fn: Arc<LifecycleThreadsafeFunction>,
+    on_stop_fn: Arc<LifecycleThreadsafeFunction>,
     dispatch_fn: Arc<DispatchThreadsafeFunction>,
+    start_timeout: Duration,
+    stop_timeout: Duration,
+    router: Arc<Router>,
 }
 
 impl DynamicWorkload {
-    pub fn new(callback: Object) -> Result<Self> {
-        let on_start: Function<'_> = callback.get_named_property("onStart")?;
-        let on_stop: Function<'_> = callback.get_named_property("onStop")?;
+    pub fn new(callback: Object, options: WorkloadOptions, router: Arc<Router>) -> Result<Self> {
+        let on_start: Function<'_, Unknown<'_>, Promise<()>> =
+            callback.get_named_property("onStart")?;
+        let on_stop: Function<'_, Unknown<'_>, Promise<()>> =
+            callback.get_named_property("onStop")?;
         let dispatch: Function<'_, Unknown<'_>, Promise<Buffer>> =
             callback.get_named_property("dispatch")?;
 
-        let on_start_fn: ThreadsafeFunction<ContextBridge> = on_start
+        let on_start_fn: LifecycleThreadsafeFunction = on_start
             .build_threadsafe_function::<ContextBridge>()
             .callee_handled::<true>()
             .build_callback(|ctx| Ok(ctx.value))?;
-        let on_stop_fn: ThreadsafeFunction<ContextBridge> = on_stop
+        let on_stop_fn: LifecycleThreadsafeFunction = on_stop
             .build_threadsafe_function::<ContextBridge>()
             .callee_handled::<true>()
             .build_callback(|ctx| Ok(ctx.value))?;
@@ -44,12 +105,26 @@ impl DynamicWorkload {
                     route_key,
                     payload,
                     request_id,
+                    metadata,
+                    traceparent,
+                    tracestate,
+                    timeout_ms,
+                    deadline_ms,
+                    caller,
+                    caller_type,
                 } = envelope;
 
                 let mut js_envelope = Object::new(&ctx.env)?;
                 js_envelope.set("routeKey", route_key)?;
                 js_envelope.set("payload", payload)?;
                 js_envelope.set("requestId", request_id)?;
+                js_envelope.set("metadata", metadata)?;
+                js_envelope.set("traceparent", traceparent)?;
+                js_envelope.set("tracestate", tracestate)?;
+                js_envelope.set("timeoutMs", timeout_ms)?;
+                js_envelope.set("deadlineMs", deadline_ms)?;
+                js_envelope.set("caller", caller)?;
+                js_envelope.set("callerType", caller_type)?;
 
                 let raw = unsafe { ToNapiValue::to_napi_value(ctx.env.raw(), js_envelope)? };
                 let js_envelope =
@@ -62,26 +137,61 @@ impl DynamicWorkload {
             on_start_fn: Arc::new(on_start_fn),
             on_stop_fn: Arc::new(on_stop_fn),
             dispatch_fn: Arc::new(dispatch_fn),
+            start_timeout: Duration::from_millis(
+                options
+                    .start_timeout_ms
+                    .unwrap_or(DEFAULT_LIFECYCLE_TIMEOUT_MS) as u64,
+            ),
+            stop_timeout: Duration::from_millis(
+                options
+                    .stop_timeout_ms
+                    .unwrap_or(DEFAULT_LIFECYCLE_TIMEOUT_MS) as u64,
+            ),
+            router,
         })
     }
 }
 
+/// Invoke a lifecycle hook and wait for the returned promise to settle.
+async fn run_lifecycle_hook(
+    hook: &LifecycleThreadsafeFunction,
+    name: &str,
+    ctx_bridge: ContextBridge,
+    timeout: Duration,
+) -> actr_protocol::ActorResult<()> {
+    let run = async {
+        let promise = hook.call_async(Ok(ctx_bridge)).await?;
+        promise.await
+    };
+
+    match tokio::time::timeout(timeout, run).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(actr_protocol::ProtocolError::InvalidStateTransition(
+            format!("{name} failed: {e}"),
+        )),
+        Err(_) => Err(actr_protocol::ProtocolError::InvalidStateTransition(
+            format!("{name} timed out after {}ms", timeout.as_millis()),
+        )),
+    }
+}
+
 #[async_trait]
 impl Workload for DynamicWorkload {
     type Dispatcher = DynamicDispatcher;
 
     async fn on_start<C: Context>(&self, ctx: &C) -> actr_protocol::ActorResult<()> {
-        let ctx_bridge = ContextBridge::try_from_context(ctx)?;
-        self.on_start_fn
-            .call(Ok(ctx_bridge), ThreadsafeFunctionCallMode::Blocking);
-        Ok(())
+        let ctx_bridge = ContextBridge::try_from_context(ctx, self.router.clone())?;
+        self.router.set_context(ctx_bridge.runtime());
+        run_lifecycle_hook(&self.on_start_fn, "onStart", ctx_bridge, self.start_timeout).await
     }
 
     async fn on_stop<C: Context>(&self, ctx: &C) -> actr_protocol::ActorResult<()> {
-        let ctx_bridge = ContextBridge::try_from_context(ctx)?;
-        self.on_stop_fn
-            .call(Ok(ctx_bridge), ThreadsafeFunctionCallMode::Blocking);
-        Ok(())
+        let ctx_bridge = ContextBridge::try_from_context(ctx, self.router.clone())?;
+        let result =
+            run_lifecycle_hook(&self.on_stop_fn, "onStop", ctx_bridge, self.stop_timeout).await;
+        // Streams still registered are closed once the hook has had its chance to close them.
+        self.router.close();
+        result
     }
 }
 
@@ -96,19 +206,49 @@ impl MessageDispatcher for DynamicDispatcher {
         envelope: actr_protocol::RpcEnvelope,
         ctx: &C,
     ) -> actr_protocol::ActorResult<bytes::Bytes> {
-        let ctx_bridge = ContextBridge::try_from_context(ctx)?;
-        let envelope_bridge = RpcEnvelopeBridge::from(envelope);
-
-        let promise = workload
-            .dispatch_fn
-            .call_async(Ok((ctx_bridge, envelope_bridge)))
-            .await
-            .map_err(|e| actr_protocol::ProtocolError::SerializationError(e.to_string()))?;
-
-        let response = promise
-            .await
-            .map_err(|e| actr_protocol::ProtocolError::SerializationError(e.to_string()))?;
-
-        Ok(bytes::Bytes::from(response.to_vec()))
+        let ctx_bridge = ContextBridge::try_from_context(ctx, workload.router.clone())?;
+        let caller = ctx_bridge.runtime().caller_id().cloned();
+
+        // Cancellation notices are consumed by the bindings and never reach the workload.
+        if envelope.route_key == crate::cancel::CANCEL_ROUTE_KEY {
+            let call_id = envelope.payload.unwrap_or_default();
+            crate::cancel::cancel_request(caller.as_ref(), &String::from_utf8_lossy(&call_id));
+            return Ok(bytes::Bytes::new());
+        }
+        if envelope.route_key == crate::tags::TAGS_ROUTE_KEY {
+            return Ok(crate::tags::encode(&workload.router.local_tags()));
+        }
+
+        let envelope_bridge =
+            RpcEnvelopeBridge::new(envelope, caller.clone(), workload.router.serial_numbers())
+                .map_err(crate::error::dispatch_error_to_protocol)?;
+        let call_id = envelope_bridge
+            .metadata
+            .iter()
+            .find(|e| e.key == crate::cancel::CALL_ID_KEY)
+            .map(|e| e.value.clone());
+        let request = RequestScope::begin(
+            caller.as_ref(),
+            call_id,
+            envelope_bridge.timeout_ms,
+            envelope_bridge.deadline_ms,
+        );
+        let _finish = request.finish_on_drop();
+        let ctx_bridge = ctx_bridge.with_request(request.clone());
+
+        let result = async {
+            let promise = workload
+                .dispatch_fn
+                .call_async(Ok((ctx_bridge, envelope_bridge)))
+                .await
+                .map_err(crate::error::dispatch_error_to_protocol)?;
+
+            promise
+                .await
+                .map_err(crate::error::dispatch_error_to_protocol)
+        }
+        .await;
+
+        Ok(bytes::Bytes::from(result?.to_vec()))
     }
 }
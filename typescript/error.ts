@@ -0,0 +1,165 @@
+/**
+ * Stable error codes reported by the native bindings.
+ */
+export const ErrorCode = {
+  Timeout: 'TIMEOUT',
+  Aborted: 'ABORTED',
+  NotFound: 'NOT_FOUND',
+  NoCandidates: 'NO_CANDIDATES',
+  CircuitOpen: 'CIRCUIT_OPEN',
+  StreamOverflow: 'STREAM_OVERFLOW',
+  StreamAborted: 'STREAM_ABORTED',
+  Unavailable: 'UNAVAILABLE',
+  UnknownRoute: 'UNKNOWN_ROUTE',
+  PermissionDenied: 'PERMISSION_DENIED',
+  Serialization: 'SERIALIZATION',
+  Deserialization: 'DESERIALIZATION',
+  Transport: 'TRANSPORT',
+  InvalidArgument: 'INVALID_ARGUMENT',
+  InvalidState: 'INVALID_STATE',
+  Application: 'APPLICATION',
+  Config: 'CONFIG',
+  Runtime: 'RUNTIME',
+  Internal: 'INTERNAL',
+} as const;
+
+export type ErrorCode = (typeof ErrorCode)[keyof typeof ErrorCode];
+
+const CODED_REASON = /^\[ACTR_([A-Z_]+)\] ([\s\S]*)$/;
+const APPLICATION_REASON = /^\[ACTR_APP:([^\]]*)\] ([\s\S]*)$/;
+const CAUSE_PREFIX = 'caused by: ';
+const DETAILS_PREFIX = 'details: ';
+
+/**
+ * Error raised by every ACTR API.
+ *
+ * `code` is stable across releases; `message` is the original runtime message and
+ * `causes` holds the source chain, outermost first.
+ *
+ * @example
+ * ```typescript
+ * try {
+ *   await ctx.callRaw(target, routeKey, PayloadType.RpcReliable, payload, 5000);
+ * } catch (e) {
+ *   if (e instanceof ActrError && e.code === ErrorCode.Timeout) {
+ *     // retry later
+ *   }
+ * }
+ * ```
+ */
+export class ActrError extends Error {
+  readonly code: ErrorCode;
+  readonly causes: string[];
+  readonly cause?: unknown;
+
+  constructor(code: ErrorCode, message: string, causes: string[] = [], cause?: unknown) {
+    super(message);
+    this.name = 'ActrError';
+    this.code = code;
+    this.causes = causes;
+    this.cause = cause;
+  }
+
+  /**
+   * Convert an error thrown by the native module into an ActrError.
+   *
+   * Errors that are already ActrError are returned unchanged; anything without a
+   * code is reported as `INTERNAL`.
+   */
+  static from(err: unknown): ActrError {
+    if (err instanceof ActrError) {
+      return err;
+    }
+    const reason = err instanceof Error ? err.message : String(err);
+    const app = APPLICATION_REASON.exec(reason);
+    if (app) {
+      return ApplicationError.decode(app[1], app[2], err);
+    }
+    const match = CODED_REASON.exec(reason);
+    if (!match) {
+      return new ActrError(ErrorCode.Internal, reason, [], err);
+    }
+    const [message, ...rest] = match[2].split('\n');
+    const causes = rest
+      .filter((line) => line.startsWith(CAUSE_PREFIX))
+      .map((line) => line.slice(CAUSE_PREFIX.length));
+    return new ActrError(match[1] as ErrorCode, message, causes, err);
+  }
+}
+
+/**
+ * Application-level error raised by a `dispatch` handler.
+ *
+ * Throw it from `Workload.dispatch` to reject the remote caller with the same
+ * `applicationCode`, `message` and optional binary `details`. Any other error thrown
+ * by a handler reaches the caller as an ApplicationError with an empty code.
+ *
+ * @example
+ * ```typescript
+ * async dispatch(ctx, envelope) {
+ *   throw new ApplicationError('OUT_OF_STOCK', 'item 42 is out of stock');
+ * }
+ *
+ * // caller side
+ * try {
+ *   await ctx.callRaw(target, routeKey, PayloadType.RpcReliable, payload, 5000);
+ * } catch (e) {
+ *   if (e instanceof ApplicationError && e.applicationCode === 'OUT_OF_STOCK') { ... }
+ * }
+ * ```
+ */
+export class ApplicationError extends ActrError {
+  readonly applicationCode: string;
+  readonly details?: Buffer;
+
+  constructor(applicationCode: string, message: string, details?: Buffer, cause?: unknown) {
+    super(ErrorCode.Application, message, [], cause);
+    this.name = 'ApplicationError';
+    this.applicationCode = applicationCode;
+    this.details = details;
+  }
+
+  /**
+   * Encode into the reason string carried back to the caller.
+   */
+  encode(): string {
+    let reason = `[ACTR_APP:${this.applicationCode}] ${this.message.replace(/\n/g, ' ')}`;
+    if (this.details && this.details.length > 0) {
+      reason += `\n${DETAILS_PREFIX}${this.details.toString('hex')}`;
+    }
+    return reason;
+  }
+
+  /** @internal */
+  static decode(applicationCode: string, body: string, cause?: unknown): ApplicationError {
+    const [message, ...rest] = body.split('\n');
+    const detailsLine = rest.find((line) => line.startsWith(DETAILS_PREFIX));
+    const details = detailsLine
+      ? Buffer.from(detailsLine.slice(DETAILS_PREFIX.length), 'hex')
+      : undefined;
+    return new ApplicationError(applicationCode, message, details, cause);
+  }
+}
+
+/**
+ * Convert an error thrown by a `dispatch` handler into the Error handed back to the
+ * native dispatcher, preserving application codes.
+ */
+export function encodeDispatchError(err: unknown): Error {
+  if (err instanceof ApplicationError) {
+    return new Error(err.encode());
+  }
+  const message = err instanceof Error ? err.message : String(err);
+  return new Error(new ApplicationError('', message).encode());
+}
+
+/**
+ * Await a native call and rethrow any failure as an ActrError.
+ */
+export async function wrapNative<T>(call: Promise<T>): Promise<T> {
+  try {
+    return await call;
+  } catch (e) {
+    throw ActrError.from(e);
+  }
+}
@@ -1,5 +1,15 @@
 export { ActrSystem } from './system';
 export { ActrNode } from './node';
 export { ActrRef } from './ref';
+export { DiscoveryWatch } from './watch';
+export {
+  DuplexStreamOptions,
+  RegisterStreamOptions,
+  StreamAdapterOptions,
+  StreamReceiver,
+  StreamWriter,
+  duplexStream,
+} from './stream';
 export { Workload } from './workload';
+export { ActrError, ApplicationError, ErrorCode } from './error';
 export * from './types';
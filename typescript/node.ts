@@ -1,5 +1,6 @@
 import { ActrNode as NativeActrNode } from '../index';
 import { ActrRef } from './ref';
+import { wrapNative } from './error';
 
 /**
  * ActrNode – an actor node that has not been started yet.
@@ -12,6 +13,9 @@ export class ActrNode {
   /**
    * Start the node and return ActrRef.
    *
+   * Waits for the workload's `onStart` to resolve; rejects if it throws or
+   * exceeds the start timeout given to `ActrSystem.attach()`.
+   *
    * @returns ActrRef instance for interacting with the actor
    *
    * @example
@@ -21,7 +25,7 @@ export class ActrNode {
    * ```
    */
   async start(): Promise<ActrRef> {
-    const nativeRef = await this.native.start();
+    const nativeRef = await wrapNative(this.native.start());
     return new ActrRef(nativeRef);
   }
 }
@@ -0,0 +1,43 @@
+import { CallOptions as NativeCallOptions, CancellationToken } from '../index';
+import { ActrError, ErrorCode } from './error';
+
+/**
+ * Per-call options for `callRaw`, `tellRaw`, `ActrRef.call` and `ActrRef.tell`.
+ */
+export interface CallOptions extends Omit<NativeCallOptions, 'cancelTokenId'> {
+  /**
+   * Abort the call. The pending call is dropped and rejects with an ActrError
+   * whose code is `ABORTED`; the remote actr-ts handler is notified when the
+   * target is known.
+   */
+  signal?: AbortSignal;
+}
+
+/**
+ * Translate TypeScript call options into native options and run `call` with them,
+ * linking `options.signal` to a native CancellationToken for the call's duration.
+ * Once the call settles, the listener is removed and the token released.
+ */
+export async function withCallOptions<T>(
+  options: CallOptions | undefined,
+  call: (native: NativeCallOptions | undefined) => Promise<T>
+): Promise<T> {
+  if (!options?.signal) {
+    return await call(options);
+  }
+
+  const { signal, ...rest } = options;
+  if (signal.aborted) {
+    throw new ActrError(ErrorCode.Aborted, 'call aborted');
+  }
+
+  const token = new CancellationToken();
+  const onAbort = () => token.cancel();
+  signal.addEventListener('abort', onAbort, { once: true });
+  try {
+    return await call({ ...rest, cancelTokenId: token.id });
+  } finally {
+    signal.removeEventListener('abort', onAbort);
+    token.dispose();
+  }
+}
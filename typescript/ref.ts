@@ -1,4 +1,18 @@
-import { ActrRef as NativeActrRef, ActrId, ActrType, PayloadType } from '../index';
+import {
+  ActrRef as NativeActrRef,
+  ActrId,
+  ActrType,
+  CacheStats,
+  CircuitStatus,
+  DiscoverOptions,
+  DiscoveredActor,
+  DiscoveryEvent,
+  PayloadType,
+  WatchOptions,
+} from '../index';
+import { wrapNative } from './error';
+import { CallOptions, withCallOptions } from './options';
+import { DiscoveryWatch } from './watch';
 
 /**
  * ActrRef – reference to a running actor.
@@ -20,29 +34,204 @@ export class ActrRef {
   /**
    * Discover actors of the given type.
    *
+   * With `options.waitMs`, re-discovers with backoff until at least
+   * `options.minCandidates` are found, and rejects with an ActrError whose
+   * code is `NO_CANDIDATES` when time runs out.
+   *
    * @param targetType - Target actor type (manufacturer + name)
    * @param count - Number of actors to discover
+   * @param options - Minimum candidate count and how long to wait for them
    * @returns List of actor IDs
    *
    * @example
    * ```typescript
    * const servers = await actorRef.discover(
    *   { manufacturer: 'acme', name: 'EchoTwiceService' },
-   *   1
+   *   1,
+   *   { waitMs: 10000 }
    * );
    * ```
    */
-  async discover(targetType: ActrType, count: number): Promise<ActrId[]> {
-    return await this.native.discover(targetType, count);
+  async discover(
+    targetType: ActrType,
+    count: number,
+    options?: DiscoverOptions
+  ): Promise<ActrId[]> {
+    return await wrapNative(this.native.discover(targetType, count, options));
+  }
+
+  /**
+   * Discover actors of the given type together with the tags they advertise.
+   *
+   * Tags are queried from each candidate and cached; actors that are not
+   * actr-ts nodes report no tags. Accepts the same options as `discover`,
+   * including a `tags` filter.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param count - Number of actors to discover
+   * @param options - Tag filter, minimum candidate count and wait time
+   * @returns Actor IDs with their tags
+   *
+   * @example
+   * ```typescript
+   * const canaries = await actorRef.discoverWithTags(echoType, 8, {
+   *   tags: { channel: 'canary' },
+   * });
+   * ```
+   */
+  async discoverWithTags(
+    targetType: ActrType,
+    count: number,
+    options?: DiscoverOptions
+  ): Promise<DiscoveredActor[]> {
+    return await wrapNative(
+      this.native.discoverWithTags(targetType, count, options)
+    );
+  }
+
+  /**
+   * Replace the tags this node advertises to discovering actors.
+   *
+   * Initial tags come from the `[tags]` table of `Actr.toml`. Other nodes
+   * see the change once their cached copy expires.
+   *
+   * @param tags - Key/value tags (e.g. version, zone, capacity)
+   */
+  setTags(tags: Record<string, string>): void {
+    this.native.setTags(tags);
+  }
+
+  /**
+   * Tags this node advertises.
+   */
+  tags(): Record<string, string> {
+    return this.native.tags();
+  }
+
+  /**
+   * Resolve an actor of the given type from the node's cached candidates.
+   *
+   * Shares the cache and load-balancing state used by `ctx.callRawByType`;
+   * discovery only runs when the cache for the type is empty or expired.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param options - Load-balancing strategy and hash key
+   * @returns Actor ID of a cached candidate
+   */
+  async resolve(
+    targetType: ActrType,
+    options?: DiscoverOptions
+  ): Promise<ActrId> {
+    return await wrapNative(this.native.resolve(targetType, options));
+  }
+
+  /**
+   * Resolve the actor of the given type that owns `shardKey` on the node's
+   * hash ring; matches the target `ctx.callRawByKey` picks for the same key.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @returns Actor ID owning the key
+   */
+  async resolveByKey(targetType: ActrType, shardKey: string): Promise<ActrId> {
+    return await wrapNative(this.native.resolveByKey(targetType, shardKey));
+  }
+
+  /**
+   * Watch actors of the given type joining and leaving.
+   *
+   * The first discovery round reports every current actor as `joined`. Events
+   * go to `listener` if given, otherwise they are buffered for `for await`.
+   * The watch stops on `close()` or when the node shuts down.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param options - Discovery interval and candidate count
+   * @param listener - Called with each event instead of buffering it
+   * @returns Running watch
+   *
+   * @example
+   * ```typescript
+   * const watch = await actorRef.watch(cacheType, { intervalMs: 2000 }, (event) => {
+   *   console.log(event.kind, formatActrId(event.id));
+   * });
+   * // later
+   * watch.close();
+   * ```
+   */
+  async watch(
+    targetType: ActrType,
+    options?: WatchOptions,
+    listener?: (event: DiscoveryEvent) => void
+  ): Promise<DiscoveryWatch> {
+    const watch = new DiscoveryWatch(listener);
+    const native = await wrapNative(
+      this.native.watch(
+        targetType,
+        (err, event) => {
+          if (!err) {
+            watch.push(event);
+          }
+        },
+        options
+      )
+    );
+    watch.attach(native);
+    return watch;
+  }
+
+  /**
+   * Drop the cached candidates of the given type so the next call re-discovers.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   */
+  invalidateTargets(targetType: ActrType): void {
+    this.native.invalidateTargets(targetType);
+  }
+
+  /**
+   * State of every circuit breaker of the node.
+   *
+   * Empty unless the `circuitBreaker` attach option is set.
+   */
+  circuitStates(): CircuitStatus[] {
+    return this.native.circuitStates();
+  }
+
+  /**
+   * Close the circuits of a target so calls reach it again right away.
+   *
+   * @param target - Target whose circuits are closed; every circuit when omitted
+   */
+  resetCircuits(target?: ActrId): void {
+    this.native.resetCircuits(target);
+  }
+
+  /**
+   * Drop cached responses so the next calls reach their targets.
+   *
+   * @param routePrefix - Only drop responses of routes starting with this prefix; all when omitted
+   */
+  invalidateCache(routePrefix?: string): void {
+    this.native.invalidateCache(routePrefix);
+  }
+
+  /**
+   * Hit, miss and eviction counters and current size of the response cache.
+   */
+  cacheStats(): CacheStats {
+    return this.native.cacheStats();
   }
 
   /**
    * Call remote actor (RPC).
    *
+   * Rejects with an ActrError whose `code` identifies the failure (e.g. `TIMEOUT`).
+   *
    * @param routeKey - Route key (e.g. 'service.Method')
    * @param payloadType - Payload type
    * @param requestPayload - Request payload (protobuf-encoded)
    * @param timeoutMs - Timeout in ms, default 30000
+   * @param options - Per-call options (metadata, idempotency key, priority, overrides, signal)
    * @returns Response payload
    *
    * @example
@@ -52,7 +241,8 @@ export class ActrRef {
    *   'echo_twice.EchoTwiceService.EchoTwice',
    *   PayloadType.RpcReliable,
    *   request,
-   *   5000
+   *   5000,
+   *   { metadata: { tenant: 'acme' } }
    * );
    * ```
    */
@@ -60,9 +250,12 @@ export class ActrRef {
     routeKey: string,
     payloadType: PayloadType,
     requestPayload: Buffer,
-    timeoutMs: number = 30000
+    timeoutMs: number = 30000,
+    options?: CallOptions
   ): Promise<Buffer> {
-    return await this.native.call(routeKey, payloadType, requestPayload, timeoutMs);
+    return await withCallOptions(options, (nativeOptions) =>
+      wrapNative(this.native.call(routeKey, payloadType, requestPayload, timeoutMs, nativeOptions))
+    );
   }
 
   /**
@@ -102,12 +295,136 @@ export class ActrRef {
     return responseType.decode(responseBuf);
   }
 
+  /**
+   * Call an actor of the given type.
+   *
+   * Shares the cached candidates and load balancing of `ctx.callRawByType`.
+   * If the chosen target turns out to be gone, the cache is dropped and the
+   * call is retried once on a freshly discovered target.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Request payload (protobuf-encoded)
+   * @param timeoutMs - Timeout in ms, default 30000
+   * @param options - Per-call options, including `loadBalance` and `tags`
+   * @returns Response payload
+   *
+   * @example
+   * ```typescript
+   * const response = await actorRef.callByType(
+   *   { manufacturer: 'acme', name: 'EchoTwiceService' },
+   *   'echo_twice.EchoTwiceService.EchoTwice',
+   *   PayloadType.RpcReliable,
+   *   Buffer.from('Hello')
+   * );
+   * ```
+   */
+  async callByType(
+    targetType: ActrType,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    timeoutMs: number = 30000,
+    options?: CallOptions
+  ): Promise<Buffer> {
+    return await withCallOptions(options, (nativeOptions) =>
+      wrapNative(
+        this.native.callByType(targetType, routeKey, payloadType, payload, timeoutMs, nativeOptions)
+      )
+    );
+  }
+
+  /**
+   * Send one-way message to an actor of the given type.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Message payload
+   * @param options - Per-call options, including `loadBalance` and `tags`
+   */
+  async tellByType(
+    targetType: ActrType,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<void> {
+    await withCallOptions(options, (nativeOptions) =>
+      wrapNative(this.native.tellByType(targetType, routeKey, payloadType, payload, nativeOptions))
+    );
+  }
+
+  /**
+   * Call the actor of the given type that owns `shardKey` on the node's hash
+   * ring; picks the same target as `ctx.callRawByKey` and `resolveByKey`.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Request payload (protobuf-encoded)
+   * @param timeoutMs - Timeout in ms, default 30000
+   * @param options - Per-call options
+   * @returns Response payload
+   */
+  async callByKey(
+    targetType: ActrType,
+    shardKey: string,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    timeoutMs: number = 30000,
+    options?: CallOptions
+  ): Promise<Buffer> {
+    return await withCallOptions(options, (nativeOptions) =>
+      wrapNative(
+        this.native.callByKey(
+          targetType,
+          shardKey,
+          routeKey,
+          payloadType,
+          payload,
+          timeoutMs,
+          nativeOptions
+        )
+      )
+    );
+  }
+
+  /**
+   * Send one-way message to the actor of the given type that owns `shardKey`.
+   *
+   * @param targetType - Target actor type (manufacturer + name)
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Message payload
+   * @param options - Per-call options
+   */
+  async tellByKey(
+    targetType: ActrType,
+    shardKey: string,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<void> {
+    await withCallOptions(options, (nativeOptions) =>
+      wrapNative(
+        this.native.tellByKey(targetType, shardKey, routeKey, payloadType, payload, nativeOptions)
+      )
+    );
+  }
+
   /**
    * Send one-way message (fire-and-forget).
    *
    * @param routeKey - Route key
    * @param payloadType - Payload type
    * @param messagePayload - Message payload
+   * @param options - Per-call options (metadata, idempotency key, priority, overrides, signal)
    *
    * @example
    * ```typescript
@@ -121,9 +438,12 @@ export class ActrRef {
   async tell(
     routeKey: string,
     payloadType: PayloadType,
-    messagePayload: Buffer
+    messagePayload: Buffer,
+    options?: CallOptions
   ): Promise<void> {
-    await this.native.tell(routeKey, payloadType, messagePayload);
+    await withCallOptions(options, (nativeOptions) =>
+      wrapNative(this.native.tell(routeKey, payloadType, messagePayload, nativeOptions))
+    );
   }
 
   /**
@@ -0,0 +1,347 @@
+import { Duplex, Readable, Writable } from 'stream';
+import { CountQueuingStrategy, ReadableStream, WritableStream } from 'stream/web';
+import {
+  ActrId,
+  StreamClosed,
+  StreamCloseReason,
+  StreamReceiver as NativeStreamReceiver,
+  StreamReceiverOptions,
+  StreamRegistrationOptions,
+  StreamSignal,
+  StreamStats,
+  StreamWriter as NativeStreamWriter,
+  StreamWriterOptions,
+} from '../index';
+import { wrapNative } from './error';
+
+/**
+ * Buffering of a Node.js or WHATWG stream adapter.
+ */
+export interface StreamAdapterOptions {
+  /**
+   * Bytes (Node.js streams) or chunks (WHATWG streams) buffered by the
+   * adapter before it stops pulling from the receiver or reports backpressure
+   * to the producer. Defaults to the stream implementation's default.
+   */
+  highWaterMark?: number;
+}
+
+/**
+ * Options for ctx.registerStream().
+ */
+export interface RegisterStreamOptions extends StreamRegistrationOptions {
+  /** Called once the registration is removed, with the reason and last sender. */
+  onClose?: (event: StreamClosed) => void;
+}
+
+/**
+ * Options for ctx.openDuplex().
+ */
+export interface DuplexStreamOptions extends StreamAdapterOptions {
+  /** Buffer size and overflow policy of the receiving side. */
+  receive?: StreamReceiverOptions;
+  /** Metadata and first sequence number of the sending side. */
+  send?: StreamWriterOptions;
+}
+
+/**
+ * StreamReceiver – buffered receiving end of a DataStream.
+ *
+ * Created via ctx.receiveStream(). Chunks are buffered natively up to
+ * `highWaterMark`; once full, the `overflow` policy either holds back the
+ * runtime until the consumer catches up or drops/fails and counts the
+ * overflow. Breaking out of a `for await` loop closes the receiver.
+ *
+ * Iteration ends once the sender calls `end()` and throws an ActrError with
+ * code `STREAM_ABORTED` once it calls `abort()`, after the chunks sent before.
+ * It also ends, after the chunks already received, when `idleTimeoutMs`
+ * passes without a chunk, the sender is no longer registered or the workload
+ * stops; `closeReason` tells which.
+ *
+ * @example
+ * ```typescript
+ * const receiver = await ctx.receiveStream('video-1', {
+ *   highWaterMark: 16,
+ *   overflow: StreamOverflow.DropOldest,
+ * });
+ * for await (const { chunk, sender } of receiver) {
+ *   render(chunk.payload);
+ * }
+ * ```
+ */
+export class StreamReceiver implements AsyncIterable<StreamSignal> {
+  /** @internal */
+  constructor(private native: NativeStreamReceiver) { }
+
+  get streamId(): string {
+    return this.native.streamId;
+  }
+
+  /**
+   * Why the receiver's registration closed, or `null` while it is open.
+   */
+  get closeReason(): StreamCloseReason | null {
+    return this.native.closeReason;
+  }
+
+  /**
+   * Chunks waiting to be read.
+   */
+  get buffered(): number {
+    return this.native.buffered;
+  }
+
+  /**
+   * Chunks dropped (or that failed the receiver) because the buffer was full.
+   */
+  get overflowCount(): number {
+    return this.native.overflowCount;
+  }
+
+  /**
+   * Delivery, loss and reorder counters of the stream.
+   */
+  stats(): StreamStats {
+    return this.native.stats();
+  }
+
+  /**
+   * Next chunk, or `null` once the receiver is closed. With a reorder
+   * window, `missed` reports sequence numbers skipped before the chunk.
+   */
+  next(): Promise<StreamSignal | null> {
+    return wrapNative(this.native.next());
+  }
+
+  /**
+   * Stop receiving and unregister the stream. Buffered chunks are discarded.
+   */
+  close(): Promise<void> {
+    return wrapNative(this.native.close());
+  }
+
+  /**
+   * Node.js Readable of the chunk payloads.
+   *
+   * Chunks are only pulled from the receiver while the Readable wants more,
+   * so a slow consumer fills the receiver's buffer and its `overflow` policy
+   * applies. Destroying the Readable closes the receiver.
+   */
+  toReadable(options?: StreamAdapterOptions): Readable {
+    const receiver = this;
+    return new Readable({
+      highWaterMark: options?.highWaterMark,
+      read() {
+        pull(this, receiver);
+      },
+      destroy(err, callback) {
+        receiver.close().then(() => callback(err), () => callback(err));
+      },
+    });
+  }
+
+  /**
+   * WHATWG ReadableStream of the chunk payloads; cancelling it closes the
+   * receiver.
+   */
+  toReadableStream(options?: StreamAdapterOptions): ReadableStream<Uint8Array> {
+    return new ReadableStream<Uint8Array>(
+      {
+        pull: async (controller) => {
+          const signal = await this.next();
+          if (signal) {
+            controller.enqueue(signal.chunk.payload);
+          } else {
+            controller.close();
+          }
+        },
+        cancel: () => this.close(),
+      },
+      new CountQueuingStrategy({ highWaterMark: options?.highWaterMark ?? 1 })
+    );
+  }
+
+  [Symbol.asyncIterator](): AsyncIterator<StreamSignal> {
+    return {
+      next: async () => {
+        const signal = await this.next();
+        return signal ? { value: signal, done: false } : { value: undefined, done: true };
+      },
+      return: async () => {
+        await this.close();
+        return { value: undefined, done: true };
+      },
+    };
+  }
+}
+
+/**
+ * StreamWriter – sending end of a DataStream.
+ *
+ * Created via ctx.openStream(). Each write becomes one chunk with the next
+ * sequence number and the current time; `end()` and `abort(reason)` tell the
+ * receiver the stream finished or failed.
+ *
+ * @example
+ * ```typescript
+ * const writer = ctx.openStream(peer, 'upload-1', { metadata: { name: 'a.bin' } });
+ * try {
+ *   for (const part of parts) {
+ *     await writer.write(part);
+ *   }
+ *   await writer.end();
+ * } catch (e) {
+ *   await writer.abort(String(e));
+ * }
+ * ```
+ */
+export class StreamWriter {
+  /** @internal */
+  constructor(private native: NativeStreamWriter) { }
+
+  get streamId(): string {
+    return this.native.streamId;
+  }
+
+  get target(): ActrId {
+    return this.native.target;
+  }
+
+  /**
+   * Whether `end()` or `abort()` has been sent.
+   */
+  get isFinished(): boolean {
+    return this.native.isFinished;
+  }
+
+  /**
+   * Send `payload` as the next chunk.
+   *
+   * @param payload - Chunk payload
+   * @param metadata - Extra metadata for this chunk only
+   */
+  write(payload: Buffer, metadata?: Record<string, string>): Promise<void> {
+    return wrapNative(this.native.write(payload, metadata));
+  }
+
+  /**
+   * Finish the stream; the receiver closes once it has read every chunk.
+   */
+  end(): Promise<void> {
+    return wrapNative(this.native.end());
+  }
+
+  /**
+   * Fail the stream; the receiver sees an ActrError with code `STREAM_ABORTED`.
+   */
+  abort(reason?: string): Promise<void> {
+    return wrapNative(this.native.abort(reason));
+  }
+
+  /**
+   * Node.js Writable sending each written buffer as one chunk.
+   *
+   * `write()` returns false while `highWaterMark` bytes are waiting to be
+   * sent. Ending the Writable ends the stream; destroying it before that
+   * aborts the stream with the error's message, or `'destroyed'` without one.
+   */
+  toWritable(options?: StreamAdapterOptions): Writable {
+    const writer = this;
+    return new Writable({
+      highWaterMark: options?.highWaterMark,
+      write(chunk: Buffer, _encoding, callback) {
+        writer.write(chunk).then(() => callback(), callback);
+      },
+      final(callback) {
+        writer.end().then(() => callback(), callback);
+      },
+      destroy(err, callback) {
+        finish(writer, err).then(() => callback(err));
+      },
+    });
+  }
+
+  /**
+   * WHATWG WritableStream sending each written buffer as one chunk; closing
+   * it ends the stream and aborting it aborts the stream.
+   */
+  toWritableStream(options?: StreamAdapterOptions): WritableStream<Uint8Array> {
+    return new WritableStream<Uint8Array>(
+      {
+        write: (chunk) =>
+          this.write(Buffer.from(chunk.buffer, chunk.byteOffset, chunk.byteLength)),
+        close: () => this.end(),
+        abort: (reason) =>
+          this.abort(reason instanceof Error ? reason.message : String(reason ?? 'aborted')),
+      },
+      new CountQueuingStrategy({ highWaterMark: options?.highWaterMark ?? 1 })
+    );
+  }
+}
+
+/**
+ * Node.js Duplex reading from `receiver` and writing to `writer`, with the
+ * backpressure of toReadable() and toWritable(). Destroying it closes the
+ * receiver and aborts the writer if it has not ended.
+ */
+export function duplexStream(
+  receiver: StreamReceiver,
+  writer: StreamWriter,
+  options?: StreamAdapterOptions
+): Duplex {
+  return new Duplex({
+    highWaterMark: options?.highWaterMark,
+    read() {
+      pull(this, receiver);
+    },
+    write(chunk: Buffer, _encoding, callback) {
+      writer.write(chunk).then(() => callback(), callback);
+    },
+    final(callback) {
+      writer.end().then(() => callback(), callback);
+    },
+    destroy(err, callback) {
+      Promise.allSettled([receiver.close(), finish(writer, err)]).then(() => callback(err));
+    },
+  });
+}
+
+const pulling = new WeakSet<Readable>();
+
+/**
+ * Push chunks from `receiver` into `readable` until it wants no more.
+ */
+function pull(readable: Readable, receiver: StreamReceiver): void {
+  if (pulling.has(readable)) {
+    return;
+  }
+  pulling.add(readable);
+  void (async () => {
+    try {
+      for (;;) {
+        const signal = await receiver.next();
+        if (!signal) {
+          readable.push(null);
+          return;
+        }
+        if (!readable.push(signal.chunk.payload)) {
+          return;
+        }
+      }
+    } catch (e) {
+      readable.destroy(e as Error);
+    } finally {
+      pulling.delete(readable);
+    }
+  })();
+}
+
+/**
+ * Abort `writer` unless the stream has already finished, with `err`'s message
+ * or `'destroyed'` when the adapter was destroyed without an error.
+ */
+async function finish(writer: StreamWriter, err: Error | null): Promise<void> {
+  if (!writer.isFinished) {
+    await writer.abort(err?.message ?? 'destroyed').catch(() => undefined);
+  }
+}
@@ -1,7 +1,22 @@
-import { ActrSystem as NativeActrSystem } from '../index';
+import {
+  ActrSystem as NativeActrSystem,
+  ContextBridge as NativeContextBridge,
+  SystemOptions,
+  WorkloadOptions,
+} from '../index';
 import { ActrNode } from './node';
-import { ActrId, Context, RpcEnvelope, PayloadType } from './types';
+import { ActrId, ActrType, CallOptions, Context, RpcEnvelope, PayloadType } from './types';
 import { Workload } from './workload';
+import { ActrError, encodeDispatchError, wrapNative } from './error';
+import { withCallOptions } from './options';
+import { duplexStream, RegisterStreamOptions, StreamReceiver, StreamWriter } from './stream';
+
+/** Native ContextBridge methods whose rejections are converted to ActrError. */
+const CONTEXT_NATIVE_METHODS = [
+  'discover',
+  'sendDataStream',
+  'unregisterStream',
+] as const;
 
 /**
  * ActrSystem – entry point for the ACTR system.
@@ -18,16 +33,21 @@ export class ActrSystem {
   /**
    * Create ActrSystem from a TOML config file path.
    *
+   * The optional `[tags]` table becomes the node's advertised tags.
+   *
    * @param configPath - Path to Actr.toml
+   * @param options - System-wide options, such as how serial numbers are handed out
    * @returns ActrSystem instance
    *
    * @example
    * ```typescript
-   * const system = await ActrSystem.fromConfig('./Actr.toml');
+   * const system = await ActrSystem.fromConfig('./Actr.toml', {
+   *   serialNumberMode: SerialNumberMode.Number,
+   * });
    * ```
    */
-  static async fromConfig(configPath: string): Promise<ActrSystem> {
-    const native = await NativeActrSystem.fromFile(configPath);
+  static async fromConfig(configPath: string, options?: SystemOptions): Promise<ActrSystem> {
+    const native = await wrapNative(NativeActrSystem.fromFile(configPath, options));
     return new ActrSystem(native);
   }
 
@@ -35,6 +55,7 @@ export class ActrSystem {
    * Attach a workload and create ActrNode.
    *
    * @param workload - Object implementing the Workload interface
+   * @param options - Lifecycle options (onStart/onStop timeouts)
    * @returns ActrNode instance
    *
    * @example
@@ -45,10 +66,10 @@ export class ActrSystem {
    *   async dispatch(ctx, envelope) { ... }
    * }
    *
-   * const node = system.attach(new MyWorkload());
+   * const node = system.attach(new MyWorkload(), { startTimeoutMs: 10000 });
    * ```
    */
-  attach(workload: Workload): ActrNode {
+  attach(workload: Workload, options?: WorkloadOptions): ActrNode {
     const nativeNode = this.native.attach({
       onStart: async (err: unknown, ctx: Context) => {
         if (err) {
@@ -73,16 +94,131 @@ export class ActrSystem {
           throw err;
         }
         this.wrapContext(ctx);
-        return await workload.dispatch(ctx, envelope);
+        try {
+          return await workload.dispatch(ctx, envelope);
+        } catch (e) {
+          throw encodeDispatchError(e);
+        }
       },
-    });
+    }, options);
     return new ActrNode(nativeNode);
   }
 
   private wrapContext(ctx: Context): void {
+    for (const method of CONTEXT_NATIVE_METHODS) {
+      const native = (ctx as any)[method].bind(ctx);
+      (ctx as any)[method] = (...args: unknown[]) => wrapNative(native(...args));
+    }
+    const bridge = ctx as unknown as NativeContextBridge;
+    const callRaw = bridge.callRaw.bind(ctx);
+    const tellRaw = bridge.tellRaw.bind(ctx);
+    const callRawByType = bridge.callRawByType.bind(ctx);
+    const tellRawByType = bridge.tellRawByType.bind(ctx);
+    const callRawByKey = bridge.callRawByKey.bind(ctx);
+    const tellRawByKey = bridge.tellRawByKey.bind(ctx);
+    const receiveStream = bridge.receiveStream.bind(ctx);
+    const openStream = bridge.openStream.bind(ctx);
+    const registerStream = bridge.registerStream.bind(ctx);
+    ctx.callRaw = (target, routeKey, payloadType, payload, timeoutMs, options) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(callRaw(target, routeKey, payloadType, payload, timeoutMs, nativeOptions))
+      );
+    ctx.tellRaw = (target, routeKey, payloadType, payload, options) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(tellRaw(target, routeKey, payloadType, payload, nativeOptions))
+      );
+    ctx.callRawByType = (targetType, routeKey, payloadType, payload, timeoutMs, options) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(
+          callRawByType(targetType, routeKey, payloadType, payload, timeoutMs, nativeOptions)
+        )
+      );
+    ctx.tellRawByType = (targetType, routeKey, payloadType, payload, options) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(tellRawByType(targetType, routeKey, payloadType, payload, nativeOptions))
+      );
+    ctx.callRawByKey = (
+      targetType,
+      shardKey,
+      routeKey,
+      payloadType,
+      payload,
+      timeoutMs,
+      options
+    ) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(
+          callRawByKey(
+            targetType,
+            shardKey,
+            routeKey,
+            payloadType,
+            payload,
+            timeoutMs,
+            nativeOptions
+          )
+        )
+      );
+    ctx.tellRawByKey = (targetType, shardKey, routeKey, payloadType, payload, options) =>
+      withCallOptions(options, (nativeOptions) =>
+        wrapNative(
+          tellRawByKey(targetType, shardKey, routeKey, payloadType, payload, nativeOptions)
+        )
+      );
+    ctx.registerStream = (streamId, callback, options) => {
+      const { onClose, ...registration }: RegisterStreamOptions = options ?? {};
+      return wrapNative(
+        registerStream(
+          streamId,
+          (err, signal) => callback(err ? ActrError.from(err) : null, signal),
+          registration,
+          onClose && ((_err, event) => onClose(event))
+        )
+      );
+    };
+    ctx.openStream = (target, streamId, options) => {
+      try {
+        return new StreamWriter(openStream(target, streamId, options));
+      } catch (e) {
+        throw ActrError.from(e);
+      }
+    };
+    ctx.receiveStream = async (streamId, options) =>
+      new StreamReceiver(await wrapNative(receiveStream(streamId, options)));
+    ctx.openDuplex = async (peer, streamId, options) => {
+      const writer = ctx.openStream(peer, streamId, options?.send);
+      const receiver = await ctx.receiveStream(streamId, options?.receive);
+      return duplexStream(receiver, writer, options);
+    };
+    const controller = new AbortController();
+    ctx.signal = controller.signal;
+    void ctx.waitCancelled().then((cancelled) => {
+      if (cancelled) {
+        controller.abort();
+      }
+    });
     if (!ctx.call) {
-      ctx.call = (target: ActrId, routeKey: string, payload: Buffer) => {
-        return ctx.callRaw(target, routeKey, PayloadType.RpcReliable, payload, 30000);
+      ctx.call = (target: ActrId, routeKey: string, payload: Buffer, options?: CallOptions) => {
+        return ctx.callRaw(target, routeKey, PayloadType.RpcReliable, payload, 30000, options);
+      };
+    }
+    if (!ctx.callByKey) {
+      ctx.callByKey = (
+        targetType: ActrType,
+        shardKey: string,
+        routeKey: string,
+        payload: Buffer,
+        options?: CallOptions
+      ) => {
+        return ctx.callRawByKey(
+          targetType,
+          shardKey,
+          routeKey,
+          PayloadType.RpcReliable,
+          payload,
+          30000,
+          options
+        );
       };
     }
   }
@@ -2,23 +2,91 @@
 import {
   ActrId,
   ActrType,
+  CacheStats,
+  CircuitBreakerOptions,
+  CircuitState,
+  CircuitStatus,
   Realm,
   PayloadType,
   DataStream,
   StreamSignal,
+  StreamStats,
+  StreamWriterOptions,
+  SystemOptions,
+  StreamOverflow,
+  StreamReceiverOptions,
+  StreamRegistrationOptions,
+  StreamCloseReason,
+  StreamClosed,
   MetadataEntry,
+  WorkloadOptions,
+  DiscoverOptions,
+  DiscoveredActor,
+  DiscoveryEvent,
+  HedgePolicy,
+  LoadBalanceStrategy,
+  ResponseCacheOptions,
+  RetryPolicy,
+  WatchOptions,
+  SerialNumberMode,
+  formatActrId,
+  parseActrId,
+  formatActrType,
+  parseActrType,
+  actrIdEquals,
+  hashActrId,
+  actrTypeEquals,
   ContextBridge as NativeContextBridge,
   RpcEnvelopeBridge as NativeRpcEnvelope,
 } from '../index';
+import { CallOptions } from './options';
+import { Duplex } from 'stream';
+import {
+  DuplexStreamOptions,
+  RegisterStreamOptions,
+  StreamReceiver,
+  StreamWriter,
+} from './stream';
+
+export { CallOptions };
 
 export {
   ActrId,
   ActrType,
+  CacheStats,
+  CircuitBreakerOptions,
+  CircuitState,
+  CircuitStatus,
   Realm,
   PayloadType,
   DataStream,
   StreamSignal,
+  StreamStats,
+  StreamWriterOptions,
+  SystemOptions,
+  StreamOverflow,
+  StreamReceiverOptions,
+  StreamRegistrationOptions,
+  StreamCloseReason,
+  StreamClosed,
   MetadataEntry,
+  WorkloadOptions,
+  DiscoverOptions,
+  DiscoveredActor,
+  DiscoveryEvent,
+  HedgePolicy,
+  LoadBalanceStrategy,
+  ResponseCacheOptions,
+  RetryPolicy,
+  WatchOptions,
+  SerialNumberMode,
+  formatActrId,
+  parseActrId,
+  formatActrType,
+  parseActrType,
+  actrIdEquals,
+  hashActrId,
+  actrTypeEquals,
 };
 
 export type RpcEnvelope = NativeRpcEnvelope;
@@ -26,19 +94,232 @@ export type RpcEnvelope = NativeRpcEnvelope;
 /**
  * Enhanced Context with helper methods.
  */
-export interface Context extends NativeContextBridge {
+export interface Context
+  extends Omit<
+    NativeContextBridge,
+    | 'callRaw'
+    | 'tellRaw'
+    | 'callRawByType'
+    | 'tellRawByType'
+    | 'callRawByKey'
+    | 'tellRawByKey'
+    | 'registerStream'
+    | 'receiveStream'
+    | 'openStream'
+  > {
+  /**
+   * Call a remote actor.
+   *
+   * @param target - Target actor ID
+   * @param routeKey - RPC route key
+   * @param payloadType - Payload type
+   * @param payload - Request payload (protobuf-encoded Buffer)
+   * @param timeoutMs - Timeout in ms
+   * @param options - Metadata, overrides and an AbortSignal
+   * @returns Response payload (protobuf-encoded Buffer)
+   */
+  callRaw(
+    target: ActrId,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    timeoutMs: number,
+    options?: CallOptions
+  ): Promise<Buffer>;
+
+  /**
+   * Send a one-way message.
+   *
+   * @param target - Target actor ID
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Message payload
+   * @param options - Metadata, overrides and an AbortSignal
+   */
+  tellRaw(
+    target: ActrId,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<void>;
+
+  /**
+   * Call an actor of the given type, using the node's cached discovery results.
+   *
+   * @param targetType - Target actor type
+   * @param routeKey - RPC route key
+   * @param payloadType - Payload type
+   * @param payload - Request payload (protobuf-encoded Buffer)
+   * @param timeoutMs - Timeout in ms
+   * @param options - Metadata, overrides and an AbortSignal
+   * @returns Response payload (protobuf-encoded Buffer)
+   */
+  callRawByType(
+    targetType: ActrType,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    timeoutMs: number,
+    options?: CallOptions
+  ): Promise<Buffer>;
+
+  /**
+   * Send a one-way message to an actor of the given type.
+   *
+   * @param targetType - Target actor type
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Message payload
+   * @param options - Metadata, overrides and an AbortSignal
+   */
+  tellRawByType(
+    targetType: ActrType,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<void>;
+
+  /**
+   * Call the actor of the given type that owns `shardKey` on the node's hash ring.
+   *
+   * @param targetType - Target actor type
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @param routeKey - RPC route key
+   * @param payloadType - Payload type
+   * @param payload - Request payload (protobuf-encoded Buffer)
+   * @param timeoutMs - Timeout in ms
+   * @param options - Metadata, overrides and an AbortSignal
+   * @returns Response payload (protobuf-encoded Buffer)
+   */
+  callRawByKey(
+    targetType: ActrType,
+    shardKey: string,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    timeoutMs: number,
+    options?: CallOptions
+  ): Promise<Buffer>;
+
+  /**
+   * Send a one-way message to the actor of the given type that owns `shardKey`.
+   *
+   * @param targetType - Target actor type
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @param routeKey - Route key
+   * @param payloadType - Payload type
+   * @param payload - Message payload
+   * @param options - Metadata, overrides and an AbortSignal
+   */
+  tellRawByKey(
+    targetType: ActrType,
+    shardKey: string,
+    routeKey: string,
+    payloadType: PayloadType,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<void>;
+
   /**
-   * Call a remote actor with default options (RpcReliable, 30s timeout).
+   * Call a remote actor (defaults: RpcReliable, 30s timeout).
    *
    * @param target - Target actor ID
    * @param routeKey - RPC route key
    * @param payload - Request payload (protobuf-encoded Buffer)
+   * @param options - Timeout, payload type and request metadata
+   * @returns Response payload (protobuf-encoded Buffer)
+   */
+  call(target: ActrId, routeKey: string, payload: Buffer, options?: CallOptions): Promise<Buffer>;
+
+  /**
+   * Call the actor of the given type that owns `shardKey` (defaults: RpcReliable, 30s timeout).
+   *
+   * @param targetType - Target actor type
+   * @param shardKey - Key mapped to one actor by consistent hashing
+   * @param routeKey - RPC route key
+   * @param payload - Request payload (protobuf-encoded Buffer)
+   * @param options - Timeout, payload type and request metadata
    * @returns Response payload (protobuf-encoded Buffer)
    */
-  call(target: ActrId, routeKey: string, payload: Buffer): Promise<Buffer>;
+  callByKey(
+    targetType: ActrType,
+    shardKey: string,
+    routeKey: string,
+    payload: Buffer,
+    options?: CallOptions
+  ): Promise<Buffer>;
+
+  /**
+   * Register a callback for an incoming DataStream.
+   *
+   * The registration is removed once the sender ends or aborts the stream, no chunk arrives
+   * within `idleTimeoutMs`, the sender is no longer registered, or the workload stops;
+   * `onClose` is then called once with the reason.
+   *
+   * @param streamId - Stream ID to receive
+   * @param callback - Called with each chunk, or an ActrError with code `STREAM_ABORTED`
+   * @param options - Idle timeout, sender check interval and `onClose`
+   */
+  registerStream(
+    streamId: string,
+    callback: (err: Error | null, signal: StreamSignal) => void,
+    options?: RegisterStreamOptions
+  ): Promise<void>;
+
+  /**
+   * Open a DataStream to `target`; the writer assigns sequence numbers and timestamps.
+   *
+   * @param target - Receiving actor ID
+   * @param streamId - Stream ID the receiver registered
+   * @param options - Metadata sent with every chunk and the first sequence number
+   * @returns Writer; finish it with `end()` or `abort(reason)`
+   */
+  openStream(target: ActrId, streamId: string, options?: StreamWriterOptions): StreamWriter;
+
+  /**
+   * Register a buffered receiver for an incoming DataStream, consumed with `for await`.
+   *
+   * @param streamId - Stream ID to receive
+   * @param options - Buffer size (`highWaterMark`), `overflow` policy and idle timeout
+   * @returns Receiver; close it (or break out of the loop) to unregister
+   */
+  receiveStream(streamId: string, options?: StreamReceiverOptions): Promise<StreamReceiver>;
+
+  /**
+   * Open a two-way DataStream with `peer` as a Node.js Duplex: chunks of `streamId` from any
+   * sender are read, and writes are sent to `peer` under the same stream ID.
+   *
+   * @param peer - Actor the written chunks are sent to
+   * @param streamId - Stream ID used in both directions
+   * @param options - highWaterMark plus receiving and sending options
+   * @returns Duplex honoring backpressure on both sides
+   *
+   * @example
+   * ```typescript
+   * const upload = await ctx.openDuplex(peer, 'upload-1');
+   * await pipeline(fs.createReadStream(file), upload);
+   * ```
+   */
+  openDuplex(peer: ActrId, streamId: string, options?: DuplexStreamOptions): Promise<Duplex>;
+
+  /**
+   * Fires when the caller cancels the request being handled or its deadline passes.
+   *
+   * Outside a request (onStart/onStop) it never fires.
+   */
+  signal: AbortSignal;
+
+  /**
+   * Get the ID of the actor that sent the current request.
+   */
+  callerId(): ActrId | null;
 
   /**
    * Get the current RPC call ID.
+   *
+   * @deprecated Returns the caller's ActrId; use `callerId()` or `envelope.caller`.
    */
   callId(): ActrId | null;
 }
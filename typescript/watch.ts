@@ -0,0 +1,99 @@
+import {
+  DiscoveryEvent,
+  DiscoveryWatch as NativeDiscoveryWatch,
+} from '../index';
+
+/**
+ * DiscoveryWatch – subscription to actors of one type joining and leaving.
+ *
+ * Created via ActrRef.watch(). Without a listener, events are buffered and
+ * consumed with `for await`; iteration ends once the watch is closed or the
+ * node shuts down.
+ *
+ * @example
+ * ```typescript
+ * const watch = await actorRef.watch(cacheType);
+ * for await (const event of watch) {
+ *   console.log(event.kind, formatActrId(event.id));
+ * }
+ * ```
+ */
+export class DiscoveryWatch implements AsyncIterable<DiscoveryEvent> {
+  private native?: NativeDiscoveryWatch;
+  private queue: DiscoveryEvent[] = [];
+  private waiters: Array<(result: IteratorResult<DiscoveryEvent>) => void> = [];
+  private ended = false;
+
+  /** @internal */
+  constructor(private listener?: (event: DiscoveryEvent) => void) { }
+
+  /** @internal Bind the native handle once the watch has started. */
+  attach(native: NativeDiscoveryWatch): void {
+    this.native = native;
+    void native.closed().then(() => this.end());
+  }
+
+  /** @internal Deliver an event from the native watch. */
+  push(event: DiscoveryEvent): void {
+    if (this.ended) {
+      return;
+    }
+    if (this.listener) {
+      this.listener(event);
+      return;
+    }
+    const waiter = this.waiters.shift();
+    if (waiter) {
+      waiter({ value: event, done: false });
+    } else {
+      this.queue.push(event);
+    }
+  }
+
+  /**
+   * Stop watching. Buffered events can still be iterated.
+   */
+  close(): void {
+    this.native?.close();
+  }
+
+  /**
+   * Whether the watch has stopped.
+   */
+  get isClosed(): boolean {
+    return this.ended;
+  }
+
+  /**
+   * Resolve once the watch has stopped.
+   */
+  async closed(): Promise<void> {
+    await this.native?.closed();
+  }
+
+  [Symbol.asyncIterator](): AsyncIterator<DiscoveryEvent> {
+    return {
+      next: () => {
+        const event = this.queue.shift();
+        if (event) {
+          return Promise.resolve({ value: event, done: false });
+        }
+        if (this.ended) {
+          return Promise.resolve({ value: undefined, done: true });
+        }
+        return new Promise((resolve) => this.waiters.push(resolve));
+      },
+      return: () => {
+        this.close();
+        return Promise.resolve({ value: undefined, done: true });
+      },
+    };
+  }
+
+  private end(): void {
+    this.ended = true;
+    for (const waiter of this.waiters.splice(0)) {
+      waiter({ value: undefined, done: true });
+    }
+  }
+}
@@ -22,8 +22,10 @@ export interface Workload {
    * Dispatch an incoming RPC message.
    *
    * @param ctx - Context for calling remote actors
-   * @param envelope - Incoming RPC envelope (routeKey, payload, requestId)
+   * @param envelope - Incoming RPC envelope (routeKey, payload, requestId, metadata,
+   *   trace context, deadline and caller)
    * @returns Response payload (protobuf-encoded Buffer)
+   * @throws ApplicationError to reject the caller with an application code and details
    */
   dispatch(ctx: Context, envelope: RpcEnvelope): Promise<Buffer>;
 }